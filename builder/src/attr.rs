@@ -0,0 +1,724 @@
+use proc_macro2::{Span, TokenTree};
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    parenthesized, token, Attribute, Error, Expr, Ident, LitBool, LitStr, Meta, Path, Result,
+    Token, Type, Visibility,
+};
+
+/// Options given by `#[builder(...)]` on the struct itself.
+#[derive(Default)]
+pub struct Container {
+    pub typestate: bool,
+    pub setter: Setter,
+    pub try_setter: Option<bool>,
+    pub validate: Validate,
+    /// `pattern = "..."`, with the span of the string for error reporting.
+    pub pattern: Option<(Pattern, Span)>,
+    pub merge: Option<(Merge, Span)>,
+    /// `serde`: the builder derives `Serialize` and `Deserialize`.
+    pub serde: Option<Span>,
+    /// `name = "..."`: the builder type, instead of `<Struct>Builder`.
+    pub name: Option<Ident>,
+    pub build_fn: BuildFn,
+    /// `struct_attrs(...)`: extra attributes of the builder struct.
+    pub struct_attrs: Vec<Meta>,
+    /// `const`: the constructor, setters and build() are `const fn`.
+    pub constant: Option<Span>,
+    /// `no_std`: the generated code only uses `core` and `alloc`.
+    pub no_std: Option<Span>,
+}
+
+/// `build_fn(name = "...", vis = "...")`
+#[derive(Default)]
+pub struct BuildFn {
+    pub name: Option<Ident>,
+    pub vis: Option<Visibility>,
+}
+
+/// How `merge` combines the `each` collections of two builders.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Merge {
+    /// Elements of the other builder are added after the existing ones.
+    Append,
+    /// A non-empty collection in the other builder replaces the existing one.
+    Replace,
+}
+
+/// How setters take and return the builder.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Pattern {
+    /// `&mut self -> &mut Self`; `build(&self)` clones the fields.
+    Mutable,
+    /// `self -> Self`; `build(self)` moves the fields out.
+    Owned,
+    /// `&self -> Self` returning a modified clone; `build(&self)` clones.
+    Immutable,
+}
+
+/// Functions called by `build()` to check cross-field invariants.
+#[derive(Default)]
+pub struct Validate {
+    /// `validate = "path"`: called with `&Struct` after it is constructed.
+    pub built: Option<Path>,
+    /// `validate(builder = "path")`: called with `&StructBuilder` before
+    /// anything is taken out of the builder.
+    pub builder: Option<Path>,
+}
+
+/// Options given by `#[builder(...)]` on a single field.
+#[derive(Default)]
+pub struct Field {
+    pub each: Option<Ident>,
+    /// `each(name = "...", item = "...")`: element type of a collection that
+    /// is not one of std's.
+    pub each_item: Option<Type>,
+    pub default: Option<DefaultValue>,
+    pub setter: Setter,
+    pub try_setter: Option<bool>,
+    pub sub_builder: Option<SubBuilder>,
+    pub merge: Option<(Merge, Span)>,
+    /// `rename = "..."`: the key of this field in serialized builders.
+    pub rename: Option<LitStr>,
+    /// `env = "VAR"`: variable read by `with_env()`.
+    pub env: Option<LitStr>,
+    /// `env_sep = "..."`: separator splitting the variable into elements.
+    pub env_sep: Option<LitStr>,
+    /// `name = "..."`: setter of a field of a tuple struct.
+    pub name: Option<Ident>,
+    /// `skip` or `skip = "expr"`, with the span of `skip`.
+    pub skip: Option<(DefaultValue, Span)>,
+    /// `optional`: the field may be left unset even though its type is not
+    /// recognizably an `Option`.
+    pub optional: Option<Span>,
+    /// `required`: the field must be set even though its type is an `Option`.
+    pub required: Option<Span>,
+    /// `group = "..."`: at most one field of the group may be set.
+    pub group: Option<LitStr>,
+    /// `exactly_one`: one field of the group must be set.
+    pub exactly_one: Option<Span>,
+    /// `requires = "..."`, possibly repeated: fields or groups that must be
+    /// set whenever this field is.
+    pub requires: Vec<LitStr>,
+    /// `conflicts_with = "..."`, possibly repeated: fields or groups that
+    /// must not be set whenever this field is.
+    pub conflicts_with: Vec<LitStr>,
+}
+
+/// `#[builder(sub_builder)]`, `#[builder(sub_builder = "path::TBuilder")]`
+/// or `#[builder(sub_builder(builder = "...", build_fn = "...", env))]`.
+pub struct SubBuilder {
+    /// Builder of the field's type, if it isn't `<Type>Builder` next to it.
+    pub builder: Option<Path>,
+    /// `build_fn = "..."`: the sub-builder's build method, if it isn't
+    /// `build`.
+    pub build_fn: Option<Ident>,
+    /// `env`: `with_env()` also calls the sub-builder's own `with_env()`.
+    pub env: Option<Span>,
+    pub span: Span,
+}
+
+/// Options given by `setter(...)` inside `#[builder(...)]`, on either the
+/// struct or a field. Unset options on a field inherit from the struct.
+#[derive(Default)]
+pub struct Setter {
+    pub into: Option<bool>,
+    /// `prefix = "..."`: prepended to setters named after their field.
+    pub prefix: Option<String>,
+    /// `name = "..."`: only on a field, the name of its setter.
+    pub name: Option<Ident>,
+    /// `vis = "..."`: visibility of the setters, `pub` by default.
+    pub vis: Option<Visibility>,
+    /// `attrs(...)`: extra attributes of the setters, added to those of the
+    /// struct on a field.
+    pub attrs: Vec<Meta>,
+    /// `strip_option = false`: setters of optional fields take the whole
+    /// `Option<T>` instead of a `T`.
+    pub strip_option: Option<bool>,
+}
+
+/// Value used by `build()` for a field that was never set.
+pub enum DefaultValue {
+    /// `#[builder(default)]`
+    Trait,
+    /// `#[builder(default = "expr")]`
+    Expr(Expr),
+}
+
+/// Collects errors so that all of them are reported at once, rather than
+/// one per compilation.
+#[derive(Default)]
+pub struct Errors(Option<Error>);
+
+impl Errors {
+    pub fn push(&mut self, error: Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self.0 {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
+}
+
+const CONTAINER_KEYS: &[&str] = &[
+    "typestate",
+    "setter",
+    "try_setter",
+    "validate",
+    "pattern",
+    "merge",
+    "serde",
+    "name",
+    "build_fn",
+    "struct_attrs",
+    "const",
+    "no_std",
+];
+
+const FIELD_KEYS: &[&str] = &[
+    "each",
+    "default",
+    "skip",
+    "setter",
+    "try_setter",
+    "sub_builder",
+    "merge",
+    "rename",
+    "name",
+    "env",
+    "env_sep",
+    "optional",
+    "required",
+    "group",
+    "exactly_one",
+    "requires",
+    "conflicts_with",
+];
+
+/// Keys that may be given more than once, each adding to the others, by the
+/// list they appear in.
+const REPEATABLE: &[(&str, &str)] = &[
+    ("builder", "setter"),
+    ("builder", "validate"),
+    ("builder", "build_fn"),
+    ("builder", "struct_attrs"),
+    ("builder", "requires"),
+    ("builder", "conflicts_with"),
+    ("setter", "attrs"),
+];
+
+/// Keys given so far, by the list they appear in.
+type Seen = Vec<(&'static str, String)>;
+
+/// Parses the options on the struct. Malformed ones are reported to `errors`
+/// and parsing goes on with the next, so that every mistake is reported.
+pub fn container(attrs: &[Attribute], errors: &mut Errors) -> Container {
+    let mut container = Container::default();
+    let mut seen = Seen::new();
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        let result = attr.parse_nested_meta(|meta| {
+            if let Some(error) = duplicate("builder", &meta, &mut seen) {
+                errors.push(error);
+            }
+            let parsed = container_key(&meta, &mut container, &mut seen, errors);
+            recovered(&meta, errors, parsed)
+        });
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
+    container
+}
+
+fn container_key(
+    meta: &ParseNestedMeta,
+    container: &mut Container,
+    seen: &mut Seen,
+    errors: &mut Errors,
+) -> Result<()> {
+    if meta.path.is_ident("typestate") {
+        container.typestate = true;
+        Ok(())
+    } else if meta.path.is_ident("setter") {
+        setter(meta, &mut container.setter, seen, errors)?;
+        match &container.setter.name {
+            Some(name) => Err(Error::new_spanned(
+                name,
+                "`setter(name = \"...\")` only applies to fields",
+            )),
+            None => Ok(()),
+        }
+    } else if meta.path.is_ident("try_setter") {
+        container.try_setter = Some(flag(meta)?);
+        Ok(())
+    } else if meta.path.is_ident("validate") {
+        validate(meta, &mut container.validate, seen, errors)
+    } else if meta.path.is_ident("pattern") {
+        let pattern: LitStr = meta.value()?.parse()?;
+        container.pattern = Some((
+            match pattern.value().as_str() {
+                "mutable" => Pattern::Mutable,
+                "owned" => Pattern::Owned,
+                "immutable" => Pattern::Immutable,
+                _ => {
+                    return Err(Error::new_spanned(
+                        pattern,
+                        "expected `pattern = \"mutable\"`, `\"owned\"` or `\"immutable\"`",
+                    ))
+                }
+            },
+            pattern.span(),
+        ));
+        Ok(())
+    } else if meta.path.is_ident("merge") {
+        container.merge = Some(merge(meta)?);
+        Ok(())
+    } else if meta.path.is_ident("serde") {
+        container.serde = Some(meta.path.span());
+        Ok(())
+    } else if meta.path.is_ident("name") {
+        let name: LitStr = meta.value()?.parse()?;
+        container.name = Some(name.parse()?);
+        Ok(())
+    } else if meta.path.is_ident("build_fn") {
+        build_fn(meta, &mut container.build_fn, seen, errors)
+    } else if meta.path.is_ident("struct_attrs") {
+        container.struct_attrs.extend(attr_list(meta)?);
+        Ok(())
+    } else if meta.path.is_ident("const") {
+        container.constant = Some(meta.path.span());
+        Ok(())
+    } else if meta.path.is_ident("no_std") {
+        container.no_std = Some(meta.path.span());
+        Ok(())
+    } else {
+        Err(unknown(
+            meta,
+            CONTAINER_KEYS,
+            meta.error(
+                "expected one of `typestate`, `setter(...)`, `try_setter`, `validate`, `pattern`, `merge`, `serde`, `name`, `build_fn(...)`, `struct_attrs(...)`, `const`, `no_std` in `builder(...)`",
+            ),
+        ))
+    }
+}
+
+/// Parses the options on a field, reporting malformed ones to `errors` like
+/// [`container`].
+pub fn field(attrs: &[Attribute], errors: &mut Errors) -> Field {
+    let mut field = Field::default();
+    let mut seen = Seen::new();
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        let result = attr.parse_nested_meta(|meta| {
+            if let Some(error) = duplicate("builder", &meta, &mut seen) {
+                errors.push(error);
+            }
+            let parsed = field_key(&meta, &mut field, &mut seen, errors);
+            recovered(&meta, errors, parsed)
+        });
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
+    field
+}
+
+fn field_key(
+    meta: &ParseNestedMeta,
+    field: &mut Field,
+    seen: &mut Seen,
+    errors: &mut Errors,
+) -> Result<()> {
+    if meta.path.is_ident("each") {
+        each(meta, field, seen, errors)
+    } else if meta.path.is_ident("default") {
+        field.default = Some(default_value(meta)?);
+        Ok(())
+    } else if meta.path.is_ident("skip") {
+        field.skip = Some((default_value(meta)?, meta.path.span()));
+        Ok(())
+    } else if meta.path.is_ident("setter") {
+        setter(meta, &mut field.setter, seen, errors)
+    } else if meta.path.is_ident("try_setter") {
+        field.try_setter = Some(flag(meta)?);
+        Ok(())
+    } else if meta.path.is_ident("sub_builder") {
+        field.sub_builder = Some(sub_builder(meta, seen, errors)?);
+        Ok(())
+    } else if meta.path.is_ident("merge") {
+        field.merge = Some(merge(meta)?);
+        Ok(())
+    } else if meta.path.is_ident("rename") {
+        field.rename = Some(meta.value()?.parse()?);
+        Ok(())
+    } else if meta.path.is_ident("name") {
+        let name: LitStr = meta.value()?.parse()?;
+        field.name = Some(name.parse()?);
+        Ok(())
+    } else if meta.path.is_ident("env") {
+        field.env = Some(meta.value()?.parse()?);
+        Ok(())
+    } else if meta.path.is_ident("env_sep") {
+        field.env_sep = Some(meta.value()?.parse()?);
+        Ok(())
+    } else if meta.path.is_ident("optional") {
+        field.optional = Some(meta.path.span());
+        Ok(())
+    } else if meta.path.is_ident("required") {
+        field.required = Some(meta.path.span());
+        Ok(())
+    } else if meta.path.is_ident("group") {
+        field.group = Some(meta.value()?.parse()?);
+        Ok(())
+    } else if meta.path.is_ident("exactly_one") {
+        field.exactly_one = Some(meta.path.span());
+        Ok(())
+    } else if meta.path.is_ident("requires") {
+        field.requires.push(meta.value()?.parse()?);
+        Ok(())
+    } else if meta.path.is_ident("conflicts_with") {
+        field.conflicts_with.push(meta.value()?.parse()?);
+        Ok(())
+    } else {
+        Err(unknown(
+            meta,
+            FIELD_KEYS,
+            meta.error(
+                "expected one of `each`, `default`, `skip`, `setter(...)`, `try_setter`, `sub_builder`, `merge`, `rename`, `name`, `env`, `env_sep`, `optional`, `required`, `group`, `exactly_one`, `requires`, `conflicts_with` in `builder(...)`",
+            ),
+        ))
+    }
+}
+
+/// Error for a key that is none of the `known` ones: a suggestion if it looks
+/// like a typo of one of them, `fallback` otherwise.
+fn unknown(meta: &ParseNestedMeta, known: &[&str], fallback: Error) -> Error {
+    let key = match meta.path.get_ident() {
+        Some(key) => key.to_string(),
+        None => return fallback,
+    };
+    match suggestion(&key, known) {
+        Some(known) => Error::new_spanned(
+            &meta.path,
+            format!("unknown attribute `{}`, did you mean `{}`?", key, known),
+        ),
+        None => fallback,
+    }
+}
+
+/// The known key closest to `key` by edit distance, if it is close enough
+/// for `key` to be a typo of it.
+fn suggestion<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|known| (edit_distance(key, known), *known))
+        .filter(|(distance, known)| *distance <= (known.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+/// Levenshtein distance: the number of characters to insert, delete or
+/// replace to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Error for a key of `list(...)` given a second time, unless it is
+/// [`REPEATABLE`]. Keys of a nested list count across all occurrences of the
+/// list, so `setter(name = "a"), setter(name = "b")` is a duplicate too.
+fn duplicate(list: &'static str, meta: &ParseNestedMeta, seen: &mut Seen) -> Option<Error> {
+    let key = meta.path.get_ident()?.to_string();
+    if REPEATABLE.contains(&(list, key.as_str())) {
+        None
+    } else if seen.contains(&(list, key.clone())) {
+        Some(Error::new_spanned(
+            &meta.path,
+            format!("duplicate `{}` in `{}(...)`", key, list),
+        ))
+    } else {
+        seen.push((list, key));
+        None
+    }
+}
+
+/// Reports the error of a malformed key to `errors`, and consumes whatever
+/// is left of it, so that the keys after it can still be parsed.
+fn recovered(meta: &ParseNestedMeta, errors: &mut Errors, parsed: Result<()>) -> Result<()> {
+    if let Err(error) = parsed {
+        errors.push(error);
+        while !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+            meta.input.parse::<TokenTree>()?;
+        }
+    }
+    Ok(())
+}
+
+/// Parses the `(...)` following `meta` one key at a time with `key`,
+/// recovering from malformed keys like the keys of `builder(...)` itself.
+fn nested(
+    meta: &ParseNestedMeta,
+    errors: &mut Errors,
+    mut key: impl FnMut(&ParseNestedMeta) -> Result<()>,
+) -> Result<()> {
+    meta.parse_nested_meta(|meta| {
+        let parsed = key(&meta);
+        recovered(&meta, errors, parsed)
+    })
+}
+
+fn each(
+    meta: &ParseNestedMeta,
+    field: &mut Field,
+    seen: &mut Seen,
+    errors: &mut Errors,
+) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        let name: LitStr = meta.value()?.parse()?;
+        field.each = Some(name.parse()?);
+        return Ok(());
+    }
+    nested(meta, errors, |nested| {
+        if let Some(error) = duplicate("each", nested, seen) {
+            return Err(error);
+        }
+        if nested.path.is_ident("name") {
+            let name: LitStr = nested.value()?.parse()?;
+            field.each = Some(name.parse()?);
+            Ok(())
+        } else if nested.path.is_ident("item") {
+            let item: LitStr = nested.value()?.parse()?;
+            field.each_item = Some(item.parse()?);
+            Ok(())
+        } else {
+            Err(unknown(
+                nested,
+                &["name", "item"],
+                nested.error("expected `each(name = \"...\", item = \"...\")`"),
+            ))
+        }
+    })?;
+    if field.each.is_none() {
+        return Err(meta.error("expected `each(name = \"...\", item = \"...\")`"));
+    }
+    Ok(())
+}
+
+fn sub_builder(meta: &ParseNestedMeta, seen: &mut Seen, errors: &mut Errors) -> Result<SubBuilder> {
+    let mut sub_builder = SubBuilder {
+        builder: None,
+        build_fn: None,
+        env: None,
+        span: meta.path.span(),
+    };
+    if meta.input.peek(Token![=]) {
+        let path: LitStr = meta.value()?.parse()?;
+        sub_builder.builder = Some(path.parse()?);
+    } else if meta.input.peek(token::Paren) {
+        nested(meta, errors, |meta| {
+            if let Some(error) = duplicate("sub_builder", meta, seen) {
+                return Err(error);
+            }
+            if meta.path.is_ident("builder") {
+                let path: LitStr = meta.value()?.parse()?;
+                sub_builder.builder = Some(path.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("build_fn") {
+                let name: LitStr = meta.value()?.parse()?;
+                sub_builder.build_fn = Some(name.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("env") {
+                sub_builder.env = Some(meta.path.span());
+                Ok(())
+            } else {
+                Err(unknown(
+                    meta,
+                    &["builder", "build_fn", "env"],
+                    meta.error(
+                        "expected `sub_builder(builder = \"...\", build_fn = \"...\", env)`",
+                    ),
+                ))
+            }
+        })?;
+    }
+    Ok(sub_builder)
+}
+
+fn setter(
+    meta: &ParseNestedMeta,
+    setter: &mut Setter,
+    seen: &mut Seen,
+    errors: &mut Errors,
+) -> Result<()> {
+    nested(meta, errors, |meta| {
+        if let Some(error) = duplicate("setter", meta, seen) {
+            return Err(error);
+        }
+        if meta.path.is_ident("into") {
+            setter.into = Some(flag(meta)?);
+            Ok(())
+        } else if meta.path.is_ident("prefix") {
+            let prefix: LitStr = meta.value()?.parse()?;
+            if syn::parse_str::<Ident>(&format!("{}x", prefix.value())).is_err() {
+                return Err(Error::new_spanned(
+                    prefix,
+                    "expected a prefix that can start an identifier, like `with_`",
+                ));
+            }
+            setter.prefix = Some(prefix.value());
+            Ok(())
+        } else if meta.path.is_ident("name") {
+            let name: LitStr = meta.value()?.parse()?;
+            setter.name = Some(name.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("vis") {
+            let vis: LitStr = meta.value()?.parse()?;
+            setter.vis = Some(vis.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("attrs") {
+            setter.attrs.extend(attr_list(meta)?);
+            Ok(())
+        } else if meta.path.is_ident("strip_option") {
+            setter.strip_option = Some(flag(meta)?);
+            Ok(())
+        } else {
+            Err(unknown(
+                meta,
+                &["into", "prefix", "name", "vis", "attrs", "strip_option"],
+                meta.error(
+                    "expected `setter(into)`, `setter(prefix = \"...\")`, `setter(name = \"...\")`, `setter(vis = \"...\")`, `setter(attrs(...))` or `setter(strip_option = false)`",
+                ),
+            ))
+        }
+    })
+}
+
+/// Parses `key(attr, ...)`, each `attr` being the contents of an attribute
+/// like `must_use` or `derive(Clone)`.
+fn attr_list(meta: &ParseNestedMeta) -> Result<Vec<Meta>> {
+    let content;
+    parenthesized!(content in meta.input);
+    let attrs = Punctuated::<Meta, Token![,]>::parse_terminated(&content)?;
+    Ok(attrs.into_iter().collect())
+}
+
+/// Parses either a bare `key`, for the `Default` value, or `key = "expr"`.
+fn default_value(meta: &ParseNestedMeta) -> Result<DefaultValue> {
+    if meta.input.peek(Token![=]) {
+        let expr: LitStr = meta.value()?.parse()?;
+        Ok(DefaultValue::Expr(expr.parse()?))
+    } else {
+        Ok(DefaultValue::Trait)
+    }
+}
+
+fn build_fn(
+    meta: &ParseNestedMeta,
+    build_fn: &mut BuildFn,
+    seen: &mut Seen,
+    errors: &mut Errors,
+) -> Result<()> {
+    nested(meta, errors, |meta| {
+        if let Some(error) = duplicate("build_fn", meta, seen) {
+            return Err(error);
+        }
+        if meta.path.is_ident("name") {
+            let name: LitStr = meta.value()?.parse()?;
+            build_fn.name = Some(name.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("vis") {
+            let vis: LitStr = meta.value()?.parse()?;
+            build_fn.vis = Some(vis.parse()?);
+            Ok(())
+        } else {
+            Err(unknown(
+                meta,
+                &["name", "vis"],
+                meta.error("expected `build_fn(name = \"...\", vis = \"...\")`"),
+            ))
+        }
+    })
+}
+
+fn validate(
+    meta: &ParseNestedMeta,
+    validate: &mut Validate,
+    seen: &mut Seen,
+    errors: &mut Errors,
+) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        // `validate` itself is repeatable so that it can be combined with
+        // `validate(...)`, but this form may only be given once.
+        let key = ("builder", "validate = \"...\"".to_owned());
+        if seen.contains(&key) {
+            return Err(Error::new_spanned(
+                &meta.path,
+                "duplicate `validate = \"...\"` in `builder(...)`",
+            ));
+        }
+        seen.push(key);
+        let path: LitStr = meta.value()?.parse()?;
+        validate.built = Some(path.parse()?);
+        return Ok(());
+    }
+    nested(meta, errors, |meta| {
+        if let Some(error) = duplicate("validate", meta, seen) {
+            return Err(error);
+        }
+        if meta.path.is_ident("builder") {
+            let path: LitStr = meta.value()?.parse()?;
+            validate.builder = Some(path.parse()?);
+            Ok(())
+        } else {
+            Err(unknown(
+                meta,
+                &["builder"],
+                meta.error("expected `validate = \"...\"` or `validate(builder = \"...\")`"),
+            ))
+        }
+    })
+}
+
+fn merge(meta: &ParseNestedMeta) -> Result<(Merge, Span)> {
+    let merge: LitStr = meta.value()?.parse()?;
+    match merge.value().as_str() {
+        "append" => Ok((Merge::Append, merge.span())),
+        "replace" => Ok((Merge::Replace, merge.span())),
+        _ => Err(Error::new_spanned(
+            merge,
+            "expected `merge = \"append\"` or `merge = \"replace\"`",
+        )),
+    }
+}
+
+/// Parses either a bare `key` or `key = true`/`key = false`.
+fn flag(meta: &ParseNestedMeta) -> Result<bool> {
+    if meta.input.peek(Token![=]) {
+        let value: LitBool = meta.value()?.parse()?;
+        Ok(value.value)
+    } else {
+        Ok(true)
+    }
+}
@@ -0,0 +1,232 @@
+//! `#[builder(const)]`: the builder can be used in `const` items. Its
+//! constructor, setters and `build()` are `const fn`, using the owned
+//! pattern, and `build()` returns the struct directly, panicking on a missing
+//! field, which is a compile error when evaluated in a const context.
+//!
+//! Whatever would call a non-const function is rejected up front: `each`,
+//! `into`, `try_setter`, `sub_builder`, and `default` without an expression.
+//! Field types with destructors are left to the compiler to reject, since
+//! setters overwrite values in place; the setter's assignment is spanned on
+//! the field's type so that the error points at the field.
+
+use crate::attr::{self, DefaultValue, Pattern};
+use crate::expand::{self, Target};
+use crate::field::{Field, Kind};
+use crate::{convert, inspect, merge, serde};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Error, Result};
+
+pub fn check(input: &DeriveInput, container: &attr::Container, fields: &[Field]) -> Result<()> {
+    let unsupported = |span: Span, what: &str| {
+        Err(Error::new(
+            span,
+            format!("{} cannot be used in a `const` builder", what),
+        ))
+    };
+
+    if let (true, Some(span)) = (container.typestate, container.constant) {
+        return Err(Error::new(
+            span,
+            "`const` cannot be combined with `typestate`",
+        ));
+    }
+    if let Some((Pattern::Mutable | Pattern::Immutable, span)) = container.pattern {
+        return Err(Error::new(
+            span,
+            "`const` builders always use the owned pattern",
+        ));
+    }
+    if let Some(param) = input.generics.type_params().next() {
+        return Err(Error::new_spanned(
+            param,
+            "`const` builders do not support type parameters, whose destructors cannot run in a const fn",
+        ));
+    }
+    if let Some(path) = container
+        .validate
+        .built
+        .iter()
+        .chain(&container.validate.builder)
+        .next()
+    {
+        return Err(Error::new_spanned(
+            path,
+            "`validate` cannot be used in a `const` builder, whose build() cannot fail",
+        ));
+    }
+
+    for field in fields {
+        let span = field.ident.span();
+        match &field.kind {
+            Kind::Each { setter, .. } => {
+                return unsupported(setter.span(), "`each`, which extends a collection,");
+            }
+            Kind::SubBuilder { .. } => return unsupported(span, "`sub_builder`"),
+            Kind::Required | Kind::Optional(_) | Kind::Skipped => {}
+        }
+        if field.into {
+            return unsupported(span, "`setter(into)`, which calls `Into::into`,");
+        }
+        if field.try_setter {
+            return unsupported(span, "`try_setter`, which calls `TryInto::try_into`,");
+        }
+        if let Some(DefaultValue::Trait) = field.default {
+            return unsupported(
+                span,
+                "`default` without an expression, which calls `Default::default()`,",
+            );
+        }
+        if let Some(env) = &field.env {
+            return Err(Error::new_spanned(
+                &env.var,
+                "`env` cannot be used in a `const` builder",
+            ));
+        }
+        if field.has_constraints() {
+            return Err(Error::new(
+                span,
+                "`group`, `requires` and `conflicts_with` cannot be used in a `const` builder, whose build() cannot fail",
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn expand(
+    input: &DeriveInput,
+    container: &attr::Container,
+    target: &Target,
+    fields: &[Field],
+) -> TokenStream {
+    let vis = &input.vis;
+    let name = target.name;
+    let builder = &target.builder;
+    let ctor = &target.ctor;
+    let path = target.path();
+    let build = &target.build;
+    let build_vis = &target.build_vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let struct_attrs = &container.struct_attrs;
+
+    let (derive_serde, mut storage): (TokenStream, Vec<TokenStream>) = if container.serde.is_some()
+    {
+        let storage = fields.iter().filter_map(|field| {
+            let attrs = serde::field(field);
+            let storage = field.storage()?;
+            Some(quote!(#attrs #storage))
+        });
+        (serde::derive(), storage.collect())
+    } else {
+        (
+            TokenStream::new(),
+            fields.iter().filter_map(Field::storage).collect(),
+        )
+    };
+    let marker = expand::marker(input, fields);
+    if let Some(marker) = &marker {
+        let skip = container.serde.map(|_| quote!(#[serde(skip)]));
+        storage.push(quote!(#skip #marker));
+    }
+    let marker_init = marker.map(|_| quote!(__marker: ::std::marker::PhantomData,));
+    let init = fields.iter().filter_map(Field::init);
+
+    let setters = fields
+        .iter()
+        .filter(|field| field.is_stored())
+        .map(|field| {
+            let ident = &field.ident;
+            let setter = &field.setter;
+            let vis = &field.vis;
+            let attrs = &field.attrs;
+            let ty = field.setter_ty();
+            let store = field.setter_store(quote!(#ident));
+            let assign = quote_spanned!(field.ty.span()=> self.#ident = #store;);
+            quote! {
+                #attrs
+                #vis const fn #setter(mut self, #ident: #ty) -> Self {
+                    #assign
+                    self
+                }
+            }
+        });
+
+    let checks = fields
+        .iter()
+        .filter(|field| field.is_required())
+        .map(|field| {
+            let ident = &field.ident;
+            let cfg = &field.cfg;
+            let message = format!("`{}` is missing required field `{}`", builder, field.name());
+            quote_spanned! {field.ty.span()=>
+                #cfg
+                {
+                    if self.#ident.is_none() {
+                        ::std::panic!(#message);
+                    }
+                }
+            }
+        });
+    // Sub-builders are rejected, so there is no error to wrap.
+    let resolve = expand::resolve(fields, Pattern::Owned, &format_ident!("{}Error", builder));
+    let members = fields.iter().map(|field| &field.member);
+    let idents = fields.iter().map(|field| &field.ident);
+    let cfgs = fields.iter().map(|field| &field.cfg);
+
+    let inspect = inspect::expand(fields, false);
+    let merge = merge::expand(fields);
+    let convert = match target.variant {
+        Some(_) => TokenStream::new(),
+        None => convert::expand(
+            input,
+            &quote!(#builder #ty_generics),
+            fields,
+            &quote!(#marker_init),
+        ),
+    };
+
+    quote! {
+        #derive_serde
+        #(#[#struct_attrs])*
+        #vis struct #builder #impl_generics #where_clause {
+            #(#storage,)*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub const fn #ctor() -> #builder #ty_generics {
+                #builder {
+                    #(#init,)*
+                    #marker_init
+                }
+            }
+        }
+
+        impl #impl_generics ::std::default::Default for #builder #ty_generics #where_clause {
+            fn default() -> Self {
+                #name::#ctor()
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            #inspect
+
+            #merge
+
+            /// Panics if a required field is not set, which in a const
+            /// context is a compile error.
+            #[allow(deprecated)]
+            #build_vis const fn #build(self) -> #name #ty_generics {
+                #(#checks)*
+                #resolve
+                #path {
+                    #(#cfgs #members: #idents,)*
+                }
+            }
+        }
+
+        #convert
+    }
+}
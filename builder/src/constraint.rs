@@ -0,0 +1,156 @@
+//! `group`, `requires` and `conflicts_with`: rules about which fields may be
+//! set together. They are checked by `build()` after the required fields, and
+//! every rule that is broken is reported at once.
+
+use crate::attr::Errors;
+use crate::field::{Field, Kind};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Ident, Result};
+
+/// Whether any field takes part in a constraint, in which case the error type
+/// has a variant reporting them.
+pub fn any(fields: &[Field]) -> bool {
+    fields.iter().any(Field::has_constraints)
+}
+
+/// Reports references to fields or groups that do not exist or to fields that
+/// are not stored as an Option, and groups named like a field, which would
+/// make references to them ambiguous.
+pub fn check(fields: &[Field]) -> Result<()> {
+    let mut errors = Errors::default();
+
+    for field in fields {
+        if let Some(group) = &field.group {
+            if fields.iter().any(|other| other.name() == group.value()) {
+                errors.push(Error::new_spanned(
+                    group,
+                    format!("group `{}` has the same name as a field", group.value()),
+                ));
+            }
+        }
+        for name in field.requires.iter().chain(&field.conflicts_with) {
+            let members = members(fields, &name.value());
+            if members.is_empty() {
+                errors.push(Error::new_spanned(
+                    name,
+                    format!("there is no field or group named `{}`", name.value()),
+                ));
+            } else if members
+                .iter()
+                .any(|member| !matches!(member.kind, Kind::Required | Kind::Optional(_)))
+            {
+                errors.push(Error::new_spanned(
+                    name,
+                    format!(
+                        "`{}` is an `each`, `sub_builder` or `skip` field, which `requires` and `conflicts_with` cannot refer to",
+                        name.value(),
+                    ),
+                ));
+            }
+        }
+    }
+
+    errors.finish()
+}
+
+/// Fields referred to by `name` in a `requires` or `conflicts_with`: the field
+/// of that name, or all fields of the group.
+fn members<'a>(fields: &'a [Field<'a>], name: &str) -> Vec<&'a Field<'a>> {
+    fields
+        .iter()
+        .filter(|field| {
+            field.name() == name
+                || field
+                    .group
+                    .as_ref()
+                    .is_some_and(|group| group.value() == name)
+        })
+        .collect()
+}
+
+/// Statements of `build()` collecting the broken constraints, and returning
+/// them as `error::ConstraintViolations` if there are any.
+pub fn expand(fields: &[Field], error: &Ident) -> TokenStream {
+    if !any(fields) {
+        return TokenStream::new();
+    }
+
+    let mut groups: Vec<String> = Vec::new();
+    for field in fields {
+        if let Some(group) = &field.group {
+            if !groups.contains(&group.value()) {
+                groups.push(group.value());
+            }
+        }
+    }
+    let group_checks = groups.iter().map(|group| {
+        let members = members(fields, group);
+        let idents = members.iter().map(|field| &field.ident);
+        let names = members
+            .iter()
+            .map(|field| format!("`{}`", field.name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let broken = if members.iter().any(|field| field.exactly_one) {
+            let message = format!("exactly one of {} must be set", names);
+            quote!(if set != 1 { violations.push(#message); })
+        } else {
+            let message = format!("at most one of {} may be set", names);
+            quote!(if set > 1 { violations.push(#message); })
+        };
+        quote! {
+            {
+                let set = [#(self.#idents.is_some()),*].iter().filter(|set| **set).count();
+                #broken
+            }
+        }
+    });
+
+    let mut field_checks = Vec::new();
+    for field in fields {
+        let ident = &field.ident;
+        let rules = field
+            .requires
+            .iter()
+            .map(|name| (name, true))
+            .chain(field.conflicts_with.iter().map(|name| (name, false)));
+        for (name, required) in rules {
+            // A field trivially requires itself and cannot conflict with
+            // itself, so it is left out of its own group.
+            let others: Vec<&Ident> = members(fields, &name.value())
+                .into_iter()
+                .filter(|other| other.ident != *ident)
+                .map(|other| &other.ident)
+                .collect();
+            if others.is_empty() {
+                continue;
+            }
+            let (condition, message) = if required {
+                (
+                    quote!(!(#(self.#others.is_some())||*)),
+                    format!("`{}` requires `{}`", field.name(), name.value()),
+                )
+            } else {
+                (
+                    quote!((#(self.#others.is_some())||*)),
+                    format!("`{}` conflicts with `{}`", field.name(), name.value()),
+                )
+            };
+            field_checks.push(quote! {
+                if self.#ident.is_some() && #condition {
+                    violations.push(#message);
+                }
+            });
+        }
+    }
+
+    quote! {
+        let mut violations = ::std::vec::Vec::new();
+        #(#group_checks)*
+        #(#field_checks)*
+        if !violations.is_empty() {
+            return ::std::result::Result::Err(#error::ConstraintViolations(violations));
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::field::{Field, Kind};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{DeriveInput, Ident, Member};
+
+/// `impl From<Struct> for StructBuilder` and `Struct::to_builder()`, for
+/// turning a finished value back into a builder holding all of its fields.
+///
+/// `builder` is the full type of the builder to produce, and `extra` any
+/// initializers for builder fields that do not correspond to a struct field.
+pub fn expand(
+    input: &DeriveInput,
+    builder: &TokenStream,
+    fields: &[Field],
+    extra: &TokenStream,
+) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let value = Ident::new("value", Span::mixed_site());
+
+    let init = fields.iter().filter_map(|field| {
+        let ident = &field.ident;
+        let member = &field.member;
+        let ty = field.ty;
+        let cfg = &field.cfg;
+        match &field.kind {
+            Kind::Required => {
+                Some(quote!(#cfg #ident: ::std::option::Option::Some(#value.#member)))
+            }
+            Kind::Optional(_) | Kind::Each { .. } => Some(quote!(#cfg #ident: #value.#member)),
+            Kind::SubBuilder { builder, .. } => Some(quote! {
+                #cfg #ident: <#builder as ::std::convert::From<#ty>>::from(#value.#member)
+            }),
+            // Computed again by build().
+            Kind::Skipped => None,
+        }
+    });
+
+    let members: Vec<&Member> = fields.iter().map(|field| &field.member).collect();
+    let cfgs = fields.iter().map(|field| &field.cfg);
+    let tys = fields.iter().map(|field| field.ty);
+    let doc = format!(
+        "Returns a builder holding a copy of every field of this `{}`.",
+        name,
+    );
+
+    // The bounds are higher-ranked so that a field type which is not Clone
+    // only makes to_builder uncallable, rather than failing to compile.
+    quote! {
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for #builder #where_clause {
+            #[allow(deprecated)]
+            fn from(#value: #name #ty_generics) -> Self {
+                Self {
+                    #(#init,)*
+                    #extra
+                }
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            #[allow(private_bounds, deprecated)]
+            pub fn to_builder(&self) -> #builder
+            where
+                #(for<'__to_builder> #tys: ::std::clone::Clone,)*
+            {
+                ::std::convert::From::from(#name {
+                    #(#cfgs #members: ::std::clone::Clone::clone(&self.#members),)*
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! `#[builder(env = "VAR")]`: `with_env()` fills fields in from environment
+//! variables, parsing them with `FromStr`. Variables that are not set leave
+//! their field alone, so the environment can be layered over values set
+//! before, and setters called afterwards override it.
+
+use crate::attr::Pattern;
+use crate::field::{Field, Item, Kind};
+use crate::ty;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Generics, Ident, Type};
+
+/// Whether the builder has `from_env()` and `with_env()`: some field has a
+/// variable, or is a sub-builder marked `sub_builder(env)`.
+pub fn any(fields: &[Field]) -> bool {
+    fields.iter().any(|field| {
+        field.env.is_some() || matches!(field.kind, Kind::SubBuilder { env: Some(_), .. })
+    })
+}
+
+/// `from_env()` and `with_env()`, if there is anything to read. Sub-builders
+/// marked `sub_builder(env)` are filled in from their own variables, with
+/// failures wrapped in the usual variant of `error`.
+pub fn expand(
+    fields: &[Field],
+    pattern: Pattern,
+    generics: &Generics,
+    error: &Ident,
+) -> TokenStream {
+    if !any(fields) {
+        return TokenStream::new();
+    }
+    let value = Ident::new("value", Span::mixed_site());
+
+    let body = pattern.setter_body(|builder| {
+        let reads = fields.iter().map(|field| {
+            let ident = &field.ident;
+            let cfg = &field.cfg;
+            if let Kind::SubBuilder {
+                variant,
+                env: Some(_),
+                ..
+            } = &field.kind
+            {
+                return quote! {
+                    #cfg
+                    {
+                        if let ::std::result::Result::Err(error) = #builder.#ident.with_env() {
+                            return ::std::result::Result::Err(#error::#variant(error));
+                        }
+                    }
+                };
+            }
+            let env = match &field.env {
+                Some(env) => env,
+                None => return TokenStream::new(),
+            };
+            let var = &env.var;
+            let name = field.name();
+            let invalid = |message: TokenStream| {
+                quote! {
+                    return ::std::result::Result::Err(#error::EnvError {
+                        variable: #var,
+                        field: #name,
+                        message: #message,
+                    })
+                }
+            };
+            let parse = |ty: &Type, input: TokenStream| {
+                let invalid = invalid(quote!(::std::string::ToString::to_string(&error)));
+                quote! {
+                    match <#ty as ::std::str::FromStr>::from_str(#input) {
+                        ::std::result::Result::Ok(parsed) => parsed,
+                        ::std::result::Result::Err(error) => #invalid,
+                    }
+                }
+            };
+            let store = match (&field.kind, &env.sep) {
+                (Kind::Each { item: Item::One(elem), .. }, Some(sep)) => {
+                    let parse = parse(elem, quote!(element));
+                    quote! {
+                        for element in #value.split(#sep).filter(|element| !element.is_empty()) {
+                            ::std::iter::Extend::extend(&mut #builder.#ident, ::std::iter::once(#parse));
+                        }
+                    }
+                }
+                (Kind::Each { item: Item::One(elem), .. }, None) => {
+                    let parse = parse(elem, quote!(&#value));
+                    quote!(::std::iter::Extend::extend(&mut #builder.#ident, ::std::iter::once(#parse));)
+                }
+                _ => {
+                    let parse = parse(field.storage_ty(), quote!(&#value));
+                    quote!(#builder.#ident = ::std::option::Option::Some(#parse);)
+                }
+            };
+            let not_unicode = invalid(quote!(::std::borrow::ToOwned::to_owned(
+                "not valid unicode"
+            )));
+            quote! {
+                #cfg
+                {
+                    if let ::std::option::Option::Some(#value) = ::std::env::var_os(#var) {
+                        let #value = match #value.into_string() {
+                            ::std::result::Result::Ok(#value) => #value,
+                            ::std::result::Result::Err(_) => #not_unicode,
+                        };
+                        #store
+                    }
+                }
+            }
+        });
+        quote!(#(#reads)*)
+    });
+
+    // Parsing needs bounds whenever the parsed type depends on the struct's
+    // type parameters.
+    let type_params: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+    let parse_bounds: Vec<&Type> = fields
+        .iter()
+        .filter(|field| field.env.is_some())
+        .map(|field| match &field.kind {
+            Kind::Each {
+                item: Item::One(elem),
+                ..
+            } => elem,
+            _ => field.storage_ty(),
+        })
+        .filter(|ty| ty::mentions(ty, &type_params))
+        .collect();
+    let clone_bound = match pattern {
+        Pattern::Immutable => quote!(Self: ::std::clone::Clone,),
+        Pattern::Mutable | Pattern::Owned => TokenStream::new(),
+    };
+    let env_where = quote! {
+        where
+            #(
+                #parse_bounds: ::std::str::FromStr,
+                <#parse_bounds as ::std::str::FromStr>::Err: ::std::fmt::Display,
+            )*
+            #clone_bound
+    };
+
+    let receiver = pattern.setter_receiver();
+    let ret = pattern.setter_ret();
+    let from_env = match pattern {
+        Pattern::Mutable => quote! {
+            let mut builder = <Self as ::std::default::Default>::default();
+            builder.with_env()?;
+            ::std::result::Result::Ok(builder)
+        },
+        Pattern::Owned | Pattern::Immutable => quote! {
+            <Self as ::std::default::Default>::default().with_env()
+        },
+    };
+
+    quote! {
+        /// A new builder with the fields read from their environment
+        /// variables, see [`with_env`](Self::with_env).
+        pub fn from_env() -> ::std::result::Result<Self, #error>
+        #env_where
+        {
+            #from_env
+        }
+
+        /// Sets every field that has an environment variable which is set,
+        /// parsing the variable's value with `FromStr`.
+        pub fn with_env(#receiver) -> ::std::result::Result<#ret, #error>
+        #env_where
+        {
+            ::std::result::Result::Ok({ #body })
+        }
+    }
+}
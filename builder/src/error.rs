@@ -0,0 +1,160 @@
+use crate::constraint;
+use crate::field::{Field, Kind};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, Visibility};
+
+pub fn ident(builder: &Ident) -> Ident {
+    format_ident!("{}Error", builder)
+}
+
+/// The error type returned by `build()`, one per builder so that callers can
+/// match on it without string comparisons.
+///
+/// Each `sub_builder` field gets a variant wrapping the error of its own
+/// builder. Messages of nested errors are rendered through the hidden
+/// `__fmt_at`, which prefixes field names with the path leading to them.
+pub fn expand(vis: &Visibility, builder: &Ident, build: &Ident, fields: &[Field]) -> TokenStream {
+    let error = ident(builder);
+    let doc = format!("Error returned by [`{}::{}`].", builder, build);
+
+    let sub_builders: Vec<(&Ident, &syn::Path, &Field)> = fields
+        .iter()
+        .filter_map(|field| match &field.kind {
+            Kind::SubBuilder { variant, error, .. } => Some((variant, error, field)),
+            _ => None,
+        })
+        .collect();
+    let variants = sub_builders.iter().map(|(variant, inner, field)| {
+        let doc = format!("Building the field `{}` failed.", field.name());
+        let cfg = &field.cfg;
+        quote! {
+            #[doc = #doc]
+            #cfg
+            #variant(#inner)
+        }
+    });
+    let fmt_arms = sub_builders.iter().map(|(variant, _, field)| {
+        let prefix = format!("{}.", field.name());
+        let cfg = &field.cfg;
+        quote! {
+            #cfg
+            #error::#variant(error) => error.__fmt_at(&::std::format!("{}{}", path, #prefix), f),
+        }
+    });
+    // Only builders reading the environment themselves can fail to parse it.
+    let env = fields.iter().any(|field| field.env.is_some());
+    let env_variant = if env {
+        quote! {
+            /// An environment variable read by `with_env()` could not be
+            /// parsed into its field.
+            EnvError {
+                variable: &'static str,
+                field: &'static str,
+                message: ::std::string::String,
+            },
+        }
+    } else {
+        TokenStream::new()
+    };
+    let constraints = constraint::any(fields);
+    let constraint_variant = if constraints {
+        quote! {
+            /// The set fields break rules given by `group`, `requires` or
+            /// `conflicts_with`, each described by a message.
+            ConstraintViolations(::std::vec::Vec<&'static str>),
+        }
+    } else {
+        TokenStream::new()
+    };
+    let constraint_arm = if constraints {
+        quote! {
+            #error::ConstraintViolations(violations) => {
+                if !path.is_empty() {
+                    ::std::write!(f, "{}: ", path.trim_end_matches('.'))?;
+                }
+                f.write_str(&violations.join("; "))
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+    let env_arm = if env {
+        quote! {
+            #error::EnvError { variable, field, message } => ::std::write!(
+                f,
+                "invalid value in environment variable `{}` for field `{}{}`: {}",
+                variable, path, field, message,
+            ),
+        }
+    } else {
+        TokenStream::new()
+    };
+    let source_arms = sub_builders.iter().map(|(variant, _, field)| {
+        let cfg = &field.cfg;
+        quote! {
+            #cfg
+            #error::#variant(error) => ::std::option::Option::Some(error),
+        }
+    });
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis enum #error {
+            /// Required fields that were never set, in declaration order.
+            UninitializedFields(::std::vec::Vec<&'static str>),
+            /// The builder's values were rejected by a validation check.
+            ValidationError(::std::string::String),
+            #env_variant
+            #constraint_variant
+            #(#variants,)*
+        }
+
+        impl #error {
+            #[doc(hidden)]
+            pub fn __fmt_at(&self, path: &str, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match self {
+                    #error::UninitializedFields(fields) => {
+                        f.write_str(if fields.len() == 1 {
+                            "missing field "
+                        } else {
+                            "missing fields "
+                        })?;
+                        for (i, field) in fields.iter().enumerate() {
+                            if i > 0 {
+                                f.write_str(", ")?;
+                            }
+                            ::std::write!(f, "`{}{}`", path, field)?;
+                        }
+                        ::std::result::Result::Ok(())
+                    }
+                    #error::ValidationError(message) => {
+                        if !path.is_empty() {
+                            ::std::write!(f, "{}: ", path.trim_end_matches('.'))?;
+                        }
+                        f.write_str(message)
+                    }
+                    #env_arm
+                    #constraint_arm
+                    #(#fmt_arms)*
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                self.__fmt_at("", f)
+            }
+        }
+
+        impl ::std::error::Error for #error {
+            fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                match self {
+                    #(#source_arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,732 @@
+use crate::attr::{Errors, Pattern};
+use crate::field::{Field, Item, Kind};
+use crate::{
+    attr, constant, constraint, convert, env, error, inspect, merge, names, no_std, serde, ty,
+    typestate,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Data, DeriveInput, Error, Fields, Ident, Meta, Path, Result, Token, Type,
+    Visibility,
+};
+
+/// What a builder builds: a struct, or one variant of an enum.
+pub struct Target<'a> {
+    /// Type returned by `build()`.
+    pub name: &'a Ident,
+    pub variant: Option<&'a Ident>,
+    pub builder: Ident,
+    /// Associated function of `name` returning a new builder.
+    pub ctor: Ident,
+    /// The builder's `build` method, possibly renamed by `build_fn(...)`.
+    pub build: Ident,
+    pub build_vis: Visibility,
+}
+
+impl Target<'_> {
+    /// Path of the struct or variant constructed by `build()`.
+    pub fn path(&self) -> TokenStream {
+        let name = self.name;
+        match self.variant {
+            Some(variant) => quote!(#name::#variant),
+            None => quote!(#name),
+        }
+    }
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    // Everything wrong with the attributes is reported at once, across the
+    // struct and all of its fields.
+    let mut errors = Errors::default();
+    let container = attr::container(&input.attrs, &mut errors);
+
+    if let (Some(span), false) = (container.serde, cfg!(feature = "serde")) {
+        errors.push(Error::new(
+            span,
+            "`#[builder(serde)]` requires the `serde` feature of derive_builder",
+        ));
+    }
+
+    let name = &input.ident;
+    let build = match &container.build_fn.name {
+        Some(build) => build.clone(),
+        None => format_ident!("build"),
+    };
+    let build_vis: Visibility = match &container.build_fn.vis {
+        Some(vis) => vis.clone(),
+        None => parse_quote!(pub),
+    };
+    let tokens = match &input.data {
+        Data::Struct(data) => {
+            let target = Target {
+                name,
+                variant: None,
+                builder: match &container.name {
+                    Some(builder) => builder.clone(),
+                    None => format_ident!("{}Builder", name),
+                },
+                ctor: format_ident!("builder"),
+                build,
+                build_vis,
+            };
+            match builder(input, &container, &target, &data.fields) {
+                Ok(builder) => builder,
+                Err(error) => {
+                    errors.push(error);
+                    TokenStream::new()
+                }
+            }
+        }
+        // Each variant gets a builder of its own, named after the variant.
+        Data::Enum(data) => {
+            if let Some(builder) = &container.name {
+                errors.push(Error::new_spanned(
+                    builder,
+                    "`name` is not supported on enums, whose builders are named after their variants",
+                ));
+            }
+            if let Some(param) = input.generics.params.first() {
+                errors.push(Error::new_spanned(
+                    param,
+                    "derive(Builder) does not support generic enums",
+                ));
+            }
+            let mut builders = TokenStream::new();
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Named(_)) {
+                    errors.push(Error::new_spanned(
+                        variant,
+                        "derive(Builder) only supports enum variants with named fields",
+                    ));
+                    continue;
+                }
+                let target = Target {
+                    name,
+                    variant: Some(&variant.ident),
+                    builder: format_ident!("{}Builder", variant.ident),
+                    ctor: snake_case(&variant.ident),
+                    build: build.clone(),
+                    build_vis: build_vis.clone(),
+                };
+                match builder(input, &container, &target, &variant.fields) {
+                    Ok(builder) => builders.extend(builder),
+                    Err(error) => errors.push(error),
+                }
+            }
+            builders
+        }
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "derive(Builder) only supports structs and enums",
+            ))
+        }
+    };
+    errors.finish()?;
+
+    match container.no_std {
+        Some(_) => Ok(no_std::rewrite(tokens)),
+        None => Ok(tokens),
+    }
+}
+
+fn builder(
+    input: &DeriveInput,
+    container: &attr::Container,
+    target: &Target,
+    fields: &Fields,
+) -> Result<TokenStream> {
+    let mut errors = Errors::default();
+    let fields: Vec<Field> = fields
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(index, field)| match Field::from_syn(index, field, container) {
+                Ok(field) => Some(field),
+                Err(error) => {
+                    errors.push(error);
+                    None
+                }
+            },
+        )
+        .collect();
+    errors.finish()?;
+    names::check(target, &fields, container.typestate)?;
+    constraint::check(&fields)?;
+
+    let env = fields
+        .iter()
+        .find_map(|field| match (&field.env, &field.kind) {
+            (Some(env), _) => Some(env.var.span()),
+            (None, Kind::SubBuilder { env, .. }) => *env,
+            (None, _) => None,
+        });
+    if let (Some(_), Some(span)) = (container.no_std, env) {
+        return Err(Error::new(
+            span,
+            "`env` is not supported together with `no_std`, which has no environment",
+        ));
+    }
+
+    if container.constant.is_some() {
+        constant::check(input, container, &fields)?;
+        Ok(constant::expand(input, container, target, &fields))
+    } else if container.typestate {
+        if let Some(field) = fields
+            .iter()
+            .find(|field| matches!(field.kind, Kind::SubBuilder { .. }))
+        {
+            return Err(Error::new_spanned(
+                &field.ident,
+                "`sub_builder` is not supported together with `typestate`, whose build() cannot fail",
+            ));
+        }
+        if let Some((Pattern::Mutable | Pattern::Immutable, span)) = container.pattern {
+            return Err(Error::new(
+                span,
+                "typestate builders always use the owned pattern",
+            ));
+        }
+        if let Some(path) = container
+            .validate
+            .built
+            .iter()
+            .chain(&container.validate.builder)
+            .next()
+        {
+            return Err(Error::new_spanned(
+                path,
+                "`validate` is not supported together with `typestate`, whose build() cannot fail",
+            ));
+        }
+        if let Some(span) = container.serde {
+            return Err(Error::new(
+                span,
+                "`serde` is not supported together with `typestate`",
+            ));
+        }
+        if let Some(env) = fields.iter().find_map(|field| field.env.as_ref()) {
+            return Err(Error::new_spanned(
+                &env.var,
+                "`env` is not supported together with `typestate`, whose required fields must be set at compile time",
+            ));
+        }
+        if let Some(field) = fields.iter().find(|field| field.has_constraints()) {
+            return Err(Error::new_spanned(
+                &field.ident,
+                "`group`, `requires` and `conflicts_with` are not supported together with `typestate`, whose build() cannot fail",
+            ));
+        }
+        Ok(typestate::expand(input, container, target, &fields))
+    } else {
+        Ok(expand(input, container, target, &fields))
+    }
+}
+
+fn expand(
+    input: &DeriveInput,
+    container: &attr::Container,
+    target: &Target,
+    fields: &[Field],
+) -> TokenStream {
+    let vis = &input.vis;
+    let name = target.name;
+    let builder = &target.builder;
+    let ctor = &target.ctor;
+    let path = target.path();
+    let error = error::ident(builder);
+    let build = &target.build;
+    let build_vis = &target.build_vis;
+    let error_type = error::expand(vis, builder, build, fields);
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let pattern = container
+        .pattern
+        .map_or(Pattern::Mutable, |(pattern, _span)| pattern);
+
+    // Except in the owned pattern, build() clones out of the builder, which
+    // needs the fields to be Clone whenever that depends on the struct's type
+    // parameters.
+    let type_params: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+    let clone_bounds: Vec<&Type> = fields
+        .iter()
+        .filter(|field| !matches!(field.kind, Kind::SubBuilder { .. } | Kind::Skipped))
+        .map(|field| field.ty)
+        .filter(|ty| pattern != Pattern::Owned && ty::mentions(ty, &type_params))
+        .collect();
+    let build_where = if clone_bounds.is_empty() {
+        TokenStream::new()
+    } else {
+        quote!(where #(#clone_bounds: ::std::clone::Clone,)*)
+    };
+
+    // Immutable setters return a modified copy of the builder.
+    let derive_clone = if pattern == Pattern::Immutable && !derives_clone(&container.struct_attrs) {
+        quote!(#[derive(Clone)])
+    } else {
+        TokenStream::new()
+    };
+    let struct_attrs = &container.struct_attrs;
+
+    // Every field, including the sub-builders, falls back to the builder's
+    // Default when missing from the input.
+    let (derive_serde, mut storage): (TokenStream, Vec<TokenStream>) = if container.serde.is_some()
+    {
+        let storage = fields.iter().filter_map(|field| {
+            let attrs = serde::field(field);
+            let storage = field.storage()?;
+            Some(quote!(#attrs #storage))
+        });
+        (serde::derive(), storage.collect())
+    } else {
+        (
+            TokenStream::new(),
+            fields.iter().filter_map(Field::storage).collect(),
+        )
+    };
+    let marker = marker(input, fields);
+    if let Some(marker) = &marker {
+        let skip = container.serde.map(|_| quote!(#[serde(skip)]));
+        storage.push(quote!(#skip #marker));
+    }
+    let marker_init = marker.map(|_| quote!(__marker: ::std::marker::PhantomData,));
+    let init = fields.iter().filter_map(Field::init);
+    let setters = fields.iter().map(|field| setter(field, pattern));
+    let build_receiver = pattern.build_receiver();
+    let merge = merge::expand(fields);
+    let env = env::expand(fields, pattern, generics, &error);
+    let inspect = inspect::expand(fields, false);
+
+    let validate_builder = container.validate.builder.as_ref().map(|path| {
+        let arg = if pattern == Pattern::Owned {
+            quote!(&self)
+        } else {
+            quote!(self)
+        };
+        quote! {
+            if let ::std::result::Result::Err(error) = #path(#arg) {
+                return ::std::result::Result::Err(#error::ValidationError(
+                    ::std::string::ToString::to_string(&error),
+                ));
+            }
+        }
+    });
+    let validate_built = container.validate.built.as_ref().map(|path| {
+        quote! {
+            if let ::std::result::Result::Err(error) = #path(&built) {
+                return ::std::result::Result::Err(#error::ValidationError(
+                    ::std::string::ToString::to_string(&error),
+                ));
+            }
+        }
+    });
+
+    // Every missing field is reported, not just the first one.
+    let check = if fields.iter().any(Field::is_required) {
+        quote! {
+            let missing = self.missing_fields();
+            if !missing.is_empty() {
+                return ::std::result::Result::Err(#error::UninitializedFields(missing));
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    let constraints = constraint::expand(fields, &error);
+    let resolve = resolve(fields, pattern, &error);
+    let convert = match target.variant {
+        Some(_) => TokenStream::new(),
+        None => convert::expand(
+            input,
+            &quote!(#builder #ty_generics),
+            fields,
+            &quote!(#marker_init),
+        ),
+    };
+    let members = fields.iter().map(|field| &field.member);
+    let idents = fields.iter().map(|field| &field.ident);
+    let cfgs = fields.iter().map(|field| &field.cfg);
+
+    quote! {
+        #error_type
+
+        #derive_clone
+        #derive_serde
+        #(#[#struct_attrs])*
+        #vis struct #builder #generics #where_clause {
+            #(#storage,)*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn #ctor() -> #builder #ty_generics {
+                #builder {
+                    #(#init,)*
+                    #marker_init
+                }
+            }
+        }
+
+        impl #impl_generics ::std::default::Default for #builder #ty_generics #where_clause {
+            fn default() -> Self {
+                #name::#ctor()
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            #inspect
+
+            #merge
+
+            #env
+
+            #[allow(deprecated)]
+            #build_vis fn #build(#build_receiver) -> ::std::result::Result<#name #ty_generics, #error>
+            #build_where
+            {
+                #validate_builder
+                #check
+                #constraints
+                #resolve
+                let built = #path {
+                    #(#cfgs #members: #idents,)*
+                };
+                #validate_built
+                ::std::result::Result::Ok(built)
+            }
+        }
+
+        #convert
+    }
+}
+
+impl Pattern {
+    pub fn setter_receiver(self) -> TokenStream {
+        match self {
+            Pattern::Mutable => quote!(&mut self),
+            Pattern::Owned => quote!(mut self),
+            Pattern::Immutable => quote!(&self),
+        }
+    }
+
+    /// Receiver of a method that only forwards to a setter.
+    fn forward_receiver(self) -> TokenStream {
+        match self {
+            Pattern::Owned => quote!(self),
+            _ => self.setter_receiver(),
+        }
+    }
+
+    fn build_receiver(self) -> TokenStream {
+        match self {
+            Pattern::Owned => quote!(self),
+            Pattern::Mutable | Pattern::Immutable => quote!(&self),
+        }
+    }
+
+    pub fn setter_ret(self) -> TokenStream {
+        match self {
+            Pattern::Mutable => quote!(&mut Self),
+            Pattern::Owned | Pattern::Immutable => quote!(Self),
+        }
+    }
+
+    /// Bounds required by setters beyond those of the builder's impl.
+    fn setter_where(self) -> TokenStream {
+        match self {
+            Pattern::Immutable => quote!(where Self: ::std::clone::Clone),
+            Pattern::Mutable | Pattern::Owned => TokenStream::new(),
+        }
+    }
+
+    /// Body of a setter that applies `update` to the builder and returns it.
+    /// `update` is given the expression naming the builder to modify.
+    pub fn setter_body(self, update: impl FnOnce(&TokenStream) -> TokenStream) -> TokenStream {
+        match self {
+            Pattern::Mutable | Pattern::Owned => {
+                let update = update(&quote!(self));
+                quote! {
+                    #update
+                    self
+                }
+            }
+            Pattern::Immutable => {
+                let copy = Ident::new("builder", Span::mixed_site());
+                let update = update(&quote!(#copy));
+                quote! {
+                    let mut #copy = ::std::clone::Clone::clone(self);
+                    #update
+                    #copy
+                }
+            }
+        }
+    }
+}
+
+fn setter(field: &Field, pattern: Pattern) -> TokenStream {
+    let ident = &field.ident;
+    let setter = &field.setter;
+    let vis = &field.vis;
+    let attrs = &field.attrs;
+    let ty = field.setter_ty();
+
+    let receiver = pattern.setter_receiver();
+    let ret = pattern.setter_ret();
+    let setter_where = pattern.setter_where();
+    let forward = pattern.forward_receiver();
+
+    if let Kind::SubBuilder { builder, .. } = &field.kind {
+        let ident_mut = format_ident!("{}_mut", ident.unraw());
+        let configure = Ident::new("configure", Span::mixed_site());
+        let body = pattern.setter_body(|b| quote!(#configure(&mut #b.#ident);));
+        return quote! {
+            #attrs
+            #vis fn #setter(
+                #receiver,
+                #configure: impl ::std::ops::FnOnce(&mut #builder) -> &mut #builder,
+            ) -> #ret #setter_where {
+                #body
+            }
+
+            #attrs
+            #vis fn #ident_mut(&mut self) -> &mut #builder {
+                &mut self.#ident
+            }
+        };
+    }
+
+    let whole = if field.has_whole_setter() {
+        let (input, value) = field.setter_input(ident, ty);
+        let try_setter = try_setter(field, setter, ty, &forward, &ret);
+        let assign = field.setter_store(value);
+        let body = pattern.setter_body(|builder| quote!(#builder.#ident = #assign;));
+        quote! {
+            #attrs
+            #vis fn #setter(#receiver, #ident: #input) -> #ret #setter_where {
+                #body
+            }
+
+            #try_setter
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    let each = each_setters(field, pattern);
+
+    quote! {
+        #whole
+        #each
+    }
+}
+
+/// The one-at-a-time setter of an `each` field and `extend_<field>`, which
+/// adds all elements of an iterator. Empty for other fields.
+pub fn each_setters(field: &Field, pattern: Pattern) -> TokenStream {
+    let (setter, item) = match &field.kind {
+        Kind::Each { setter, item, .. } => (setter, item),
+        _ => return TokenStream::new(),
+    };
+    let ident = &field.ident;
+    let vis = &field.vis;
+    let attrs = &field.attrs;
+    let receiver = pattern.setter_receiver();
+    let ret = pattern.setter_ret();
+    let setter_where = pattern.setter_where();
+    let forward = pattern.forward_receiver();
+
+    let (params, value, try_setter) = match item {
+        Item::One(elem) => {
+            let (input, value) = field.setter_input(setter, elem);
+            let try_setter = try_setter(field, setter, elem, &forward, &ret);
+            (quote!(#setter: #input), value, try_setter)
+        }
+        // The key and value could fail to convert with different errors, so
+        // map entries have no try_ setter.
+        Item::KeyValue(key_ty, value_ty) => {
+            let key_arg = Ident::new("key", Span::mixed_site());
+            let value_arg = Ident::new("value", Span::mixed_site());
+            let (key_input, key) = field.setter_input(&key_arg, key_ty);
+            let (value_input, value) = field.setter_input(&value_arg, value_ty);
+            (
+                quote!(#key_arg: #key_input, #value_arg: #value_input),
+                quote!((#key, #value)),
+                TokenStream::new(),
+            )
+        }
+    };
+    let body = pattern.setter_body(|builder| {
+        quote!(::std::iter::Extend::extend(&mut #builder.#ident, ::std::iter::once(#value));)
+    });
+
+    let extend = format_ident!("extend_{}", ident.unraw());
+    let items = Ident::new("items", Span::mixed_site());
+    let item_ty = item.ty();
+    let extend_body = pattern
+        .setter_body(|builder| quote!(::std::iter::Extend::extend(&mut #builder.#ident, #items);));
+
+    quote! {
+        #attrs
+        #vis fn #setter(#receiver, #params) -> #ret #setter_where {
+            #body
+        }
+
+        #try_setter
+
+        #attrs
+        #vis fn #extend(
+            #receiver,
+            #items: impl ::std::iter::IntoIterator<Item = #item_ty>,
+        ) -> #ret #setter_where {
+            #extend_body
+        }
+    }
+}
+
+/// Statements binding each field's final value to a local of the same name,
+/// in declaration order so that a `default = "..."` expression can refer to
+/// the fields before it. The caller has made sure required fields are set.
+///
+/// The owned pattern moves values out of `self`; the others clone them.
+/// Failures of sub-builders are returned as the matching variant of `error`.
+pub fn resolve(fields: &[Field], pattern: Pattern, error: &Ident) -> TokenStream {
+    let (bind, take) = match pattern {
+        Pattern::Owned => (quote!(value), quote!(value)),
+        Pattern::Mutable | Pattern::Immutable => {
+            (quote!(ref value), quote!(::std::clone::Clone::clone(value)))
+        }
+    };
+
+    let statements = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let value = match (&field.kind, field.default_value()) {
+            (Kind::Required, None) => quote! {
+                match self.#ident {
+                    ::std::option::Option::Some(#bind) => #take,
+                    ::std::option::Option::None => ::std::unreachable!(),
+                }
+            },
+            (Kind::Required, Some(default)) => quote! {
+                match self.#ident {
+                    ::std::option::Option::Some(#bind) => #take,
+                    ::std::option::Option::None => #default,
+                }
+            },
+            (Kind::Optional(_), Some(default)) => quote! {
+                match self.#ident {
+                    ::std::option::Option::Some(#bind) => ::std::option::Option::Some(#take),
+                    ::std::option::Option::None => #default,
+                }
+            },
+            (Kind::SubBuilder { variant, build, .. }, _) => {
+                // Spanned on the field, so that a sub-builder whose build
+                // method takes `self` is reported there, not at the derive.
+                let sub_builder = quote_spanned!(field.ty.span()=> self.#ident);
+                quote! {
+                    match #sub_builder.#build() {
+                        ::std::result::Result::Ok(value) => value,
+                        ::std::result::Result::Err(error) => {
+                            return ::std::result::Result::Err(#error::#variant(error));
+                        }
+                    }
+                }
+            }
+            // The default is always set for a skipped field.
+            (Kind::Skipped, default) => {
+                default.unwrap_or_else(|| quote!(::std::default::Default::default()))
+            }
+            (Kind::Optional(_), None) | (Kind::Each { .. }, _) => match pattern {
+                Pattern::Owned => quote!(self.#ident),
+                Pattern::Mutable | Pattern::Immutable => {
+                    quote!(::std::clone::Clone::clone(&self.#ident))
+                }
+            },
+        };
+        let cfg = &field.cfg;
+        quote!(#cfg let #ident = #value;)
+    });
+
+    quote!(#(#statements)*)
+}
+
+/// `try_<setter>`, which converts its argument with `TryInto` and hands the
+/// result to the infallible setter of the same name. Empty unless the field
+/// asked for one.
+pub fn try_setter(
+    field: &Field,
+    setter: &Ident,
+    ty: &Type,
+    receiver: &TokenStream,
+    ret: &TokenStream,
+) -> TokenStream {
+    if !field.try_setter {
+        return TokenStream::new();
+    }
+    let try_setter = format_ident!("try_{}", setter.unraw());
+    let vis = &field.vis;
+    let attrs = &field.attrs;
+    // Allowed since the setter it calls may be deprecated along with it.
+    quote! {
+        #attrs
+        #[allow(deprecated)]
+        #vis fn #try_setter<__Value: ::std::convert::TryInto<#ty>>(
+            #receiver,
+            #setter: __Value,
+        ) -> ::std::result::Result<#ret, <__Value as ::std::convert::TryInto<#ty>>::Error> {
+            let #setter = ::std::convert::TryInto::try_into(#setter)?;
+            ::std::result::Result::Ok(self.#setter(#setter))
+        }
+    }
+}
+
+/// Storage standing in for the struct's lifetimes and type parameters, which
+/// may only be used by skipped fields that the builder does not hold. Without
+/// it they would be unused parameters of the builder. Naming the struct
+/// itself also gives the builder the struct's inferred outlives bounds.
+pub fn marker(input: &DeriveInput, fields: &[Field]) -> Option<TokenStream> {
+    if input.generics.params.is_empty()
+        || !fields
+            .iter()
+            .any(|field| matches!(field.kind, Kind::Skipped))
+    {
+        return None;
+    }
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    Some(quote!(__marker: ::std::marker::PhantomData<fn() -> #name #ty_generics>))
+}
+
+/// Whether `struct_attrs(...)` already derives Clone for the builder, which
+/// the immutable pattern would otherwise derive a second time.
+fn derives_clone(struct_attrs: &[Meta]) -> bool {
+    struct_attrs.iter().any(|attr| match attr {
+        Meta::List(list) if list.path.is_ident("derive") => list
+            .parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+            .is_ok_and(|paths| {
+                paths.iter().any(|path| {
+                    path.segments
+                        .last()
+                        .is_some_and(|last| last.ident == "Clone")
+                })
+            }),
+        _ => false,
+    })
+}
+
+/// `RoundedRect` -> `rounded_rect`, as a raw identifier if that is a keyword.
+fn snake_case(ident: &Ident) -> Ident {
+    let mut snake = String::new();
+    for (i, ch) in ident.unraw().to_string().char_indices() {
+        if ch.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.extend(ch.to_lowercase());
+    }
+    syn::parse_str(&snake).unwrap_or_else(|_| Ident::new_raw(&snake, ident.span()))
+}
@@ -0,0 +1,526 @@
+use crate::attr::{self, DefaultValue, Errors, Merge};
+use crate::ty::{self, Collection};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{parse_quote, Attribute, Error, Ident, LitStr, Member, Path, Result, Type, Visibility};
+
+pub struct Field<'a> {
+    /// Name of the field's storage and setter in the builder: the field's own
+    /// name, or `_0`, `_1`, ... in a tuple struct unless given a `name`.
+    pub ident: Ident,
+    /// The field as accessed on the struct: `name` or `0`.
+    pub member: Member,
+    /// Name of the setter taking the whole value, `ident` unless renamed or
+    /// prefixed through `setter(...)`.
+    pub setter: Ident,
+    /// Visibility of all the setters of this field.
+    pub vis: Visibility,
+    pub ty: &'a Type,
+    pub kind: Kind<'a>,
+    pub default: Option<DefaultValue>,
+    /// Setters accept `impl Into<T>` instead of `T`.
+    pub into: bool,
+    /// Every setter has a `try_` counterpart accepting `impl TryInto<T>`.
+    pub try_setter: bool,
+    /// The setter of an optional field takes the `T` of its `Option<T>`,
+    /// rather than the `Option<T>` itself.
+    pub strip_option: bool,
+    /// Key of the field when the builder is serialized.
+    pub rename: Option<LitStr>,
+    pub env: Option<Env>,
+    /// `group = "..."`, checked by `build()` along with `requires` and
+    /// `conflicts_with`.
+    pub group: Option<LitStr>,
+    /// The field's group must have exactly one of its fields set.
+    pub exactly_one: bool,
+    pub requires: Vec<LitStr>,
+    pub conflicts_with: Vec<LitStr>,
+    /// `#[cfg]` and `#[cfg_attr]` of the field, repeated on everything
+    /// generated for it so that it disappears from the builder along with
+    /// the field.
+    pub cfg: TokenStream,
+    /// The conditions of the field's `#[cfg]` attributes combined with
+    /// `all(...)`, if it has any.
+    pub cfg_predicate: Option<TokenStream>,
+    /// Attributes of every setter: `cfg`, the field's `#[doc]` and
+    /// `#[deprecated]`, and those given by `setter(attrs(...))`.
+    pub attrs: TokenStream,
+}
+
+/// `#[builder(env = "...")]`, read by the generated `with_env()`.
+pub struct Env {
+    pub var: LitStr,
+    /// `env_sep = "..."` on an `each` field; without one the whole value is a
+    /// single element.
+    pub sep: Option<LitStr>,
+}
+
+pub enum Kind<'a> {
+    /// Must be set before `build()`.
+    Required,
+    /// Declared as `Option<T>`, or marked `optional`; the setter takes `T`
+    /// and may be skipped.
+    Optional(&'a Type),
+    /// `#[builder(each = "...")]`; the setter adds one element at a time.
+    Each {
+        setter: Ident,
+        item: Item<'a>,
+        merge: Merge,
+    },
+    /// `#[builder(skip)]`; not stored in the builder at all, `build()` always
+    /// uses the default value.
+    Skipped,
+    /// `#[builder(sub_builder)]`; the builder holds the field type's own
+    /// builder and builds it as part of `build()`.
+    SubBuilder {
+        builder: Path,
+        error: Path,
+        /// Variant of the outer error type wrapping `error`.
+        variant: Ident,
+        /// The sub-builder's build method.
+        build: Ident,
+        /// `sub_builder(env)`: filled in by the outer `with_env()`.
+        env: Option<Span>,
+    },
+}
+
+/// What the collection of an `each` field is extended with.
+pub enum Item<'a> {
+    /// A single value, taken by the setter as its one argument.
+    One(Box<Type>),
+    /// A map entry, taken by the setter as separate key and value arguments.
+    KeyValue(&'a Type, &'a Type),
+}
+
+impl Item<'_> {
+    /// Type of the elements of the collection.
+    pub fn ty(&self) -> TokenStream {
+        match self {
+            Item::One(ty) => quote!(#ty),
+            Item::KeyValue(key, value) => quote!((#key, #value)),
+        }
+    }
+}
+
+impl<'a> Field<'a> {
+    pub fn from_syn(
+        index: usize,
+        field: &'a syn::Field,
+        container: &attr::Container,
+    ) -> Result<Self> {
+        let ty = &field.ty;
+        let mut errors = Errors::default();
+        let attrs = attr::field(&field.attrs, &mut errors);
+        errors.finish()?;
+
+        let (ident, member) = match (&field.ident, attrs.name) {
+            (Some(ident), None) => (ident.clone(), Member::Named(ident.clone())),
+            (None, name) => (
+                name.unwrap_or_else(|| format_ident!("_{}", index)),
+                Member::Unnamed(index.into()),
+            ),
+            (Some(_), Some(name)) => {
+                return Err(Error::new_spanned(
+                    name,
+                    "`name` only applies to fields of tuple structs",
+                ))
+            }
+        };
+
+        if let Some((_, span)) = &attrs.skip {
+            if attrs.each.is_some()
+                || attrs.default.is_some()
+                || attrs.sub_builder.is_some()
+                || attrs.env.is_some()
+            {
+                return Err(Error::new(
+                    *span,
+                    "`skip` cannot be combined with `each`, `default`, `sub_builder` or `env`",
+                ));
+            }
+        }
+
+        if let (Some(each), Some(_)) = (&attrs.each, &attrs.default) {
+            return Err(Error::new_spanned(
+                each,
+                "`each` fields already default to an empty collection",
+            ));
+        }
+
+        if let (None, Some((_, span))) = (&attrs.each, attrs.merge) {
+            return Err(Error::new(span, "`merge` only applies to `each` fields"));
+        }
+
+        if let (Some(rename), None) = (&attrs.rename, container.serde) {
+            return Err(Error::new_spanned(
+                rename,
+                "`rename` only applies to builders with `#[builder(serde)]`",
+            ));
+        }
+
+        if let Some(sep) = &attrs.env_sep {
+            if attrs.env.is_none() || attrs.each.is_none() {
+                return Err(Error::new_spanned(
+                    sep,
+                    "`env_sep` only applies to `each` fields with `env = \"...\"`",
+                ));
+            }
+        }
+
+        if let (Some(var), Some(_)) = (&attrs.env, &attrs.sub_builder) {
+            return Err(Error::new_spanned(
+                var,
+                "`env = \"...\"` cannot be used on a `sub_builder` field, use `sub_builder(env)` to read its own fields' variables",
+            ));
+        }
+
+        if let Some(sub_builder) = &attrs.sub_builder {
+            if attrs.each.is_some() || attrs.default.is_some() {
+                return Err(Error::new(
+                    sub_builder.span,
+                    "`sub_builder` cannot be combined with `each` or `default`",
+                ));
+            }
+            // Immutable setters copy the builder, which would need every
+            // sub-builder to be Clone.
+            if let Some((attr::Pattern::Immutable, _)) = container.pattern {
+                return Err(Error::new(
+                    sub_builder.span,
+                    "`sub_builder` is not supported together with `pattern = \"immutable\"`",
+                ));
+            }
+        }
+
+        if let (Some(_), Some(span)) = (attrs.optional, attrs.required) {
+            return Err(Error::new(
+                span,
+                "a field cannot be both `optional` and `required`",
+            ));
+        }
+
+        if let Some(span) = attrs.optional.or(attrs.required) {
+            if attrs.each.is_some() || attrs.sub_builder.is_some() || attrs.skip.is_some() {
+                return Err(Error::new(
+                    span,
+                    "`optional` and `required` cannot be combined with `each`, `sub_builder` or `skip`",
+                ));
+            }
+        }
+
+        if let (Some(span), Some(_)) = (attrs.required, &attrs.default) {
+            return Err(Error::new(
+                span,
+                "a `required` field cannot have a `default`",
+            ));
+        }
+
+        if let (None, Some(span)) = (&attrs.group, attrs.exactly_one) {
+            return Err(Error::new(
+                span,
+                "`exactly_one` only applies to fields with a `group = \"...\"`",
+            ));
+        }
+
+        let mut default = attrs.default;
+        let kind = if let Some((value, _span)) = attrs.skip {
+            default = Some(value);
+            Kind::Skipped
+        } else if let Some(sub_builder) = attrs.sub_builder {
+            let builder = match sub_builder.builder {
+                Some(builder) => builder,
+                None => match ty {
+                    Type::Path(ty) if ty.qself.is_none() => ty::suffixed(&ty.path, "Builder", true),
+                    _ => return Err(Error::new_spanned(
+                        ty,
+                        "`sub_builder` requires a field whose type is a path, like `SandboxConfig`",
+                    )),
+                },
+            };
+            Kind::SubBuilder {
+                error: ty::suffixed(&builder, "Error", false),
+                builder,
+                variant: variant(&ident),
+                build: sub_builder
+                    .build_fn
+                    .unwrap_or_else(|| format_ident!("build")),
+                env: sub_builder.env,
+            }
+        } else if let Some(setter) = attrs.each {
+            let item = match (attrs.each_item, ty::collection(ty)) {
+                (Some(item), _) => Item::One(Box::new(item)),
+                (None, Some(Collection::Sequence(elem))) => Item::One(Box::new(elem.clone())),
+                (None, Some(Collection::Map(key, value))) => Item::KeyValue(key, value),
+                (None, None) => {
+                    return Err(Error::new_spanned(
+                        ty,
+                        "`each` requires a collection like `Vec<T>` or `HashMap<K, V>`, or the element type given with `each(name = \"...\", item = \"...\")`",
+                    ))
+                }
+            };
+            if let (Item::KeyValue(..), Some(var)) = (&item, &attrs.env) {
+                return Err(Error::new_spanned(
+                    var,
+                    "`env` cannot fill in a map, whose entries have no `FromStr`",
+                ));
+            }
+            let merge = attrs
+                .merge
+                .or(container.merge)
+                .map_or(Merge::Append, |(merge, _span)| merge);
+            Kind::Each {
+                setter,
+                item,
+                merge,
+            }
+        } else if attrs.required.is_some() {
+            Kind::Required
+        } else if let Some(inner) = ty::option_inner(ty) {
+            Kind::Optional(inner)
+        } else if attrs.optional.is_some() {
+            match ty::only_arg(ty) {
+                Some(inner) => Kind::Optional(inner),
+                None => {
+                    return Err(Error::new_spanned(
+                        ty,
+                        "`optional` requires an alias of `Option` with the inner type as its only argument, like `Maybe<T>`",
+                    ))
+                }
+            }
+        } else {
+            Kind::Required
+        };
+
+        if let (Some(_), false) = (attrs.setter.strip_option, matches!(kind, Kind::Optional(_))) {
+            return Err(Error::new_spanned(
+                ty,
+                "`strip_option` only applies to fields of type `Option<T>` or marked `optional`",
+            ));
+        }
+
+        let prefix = attrs
+            .setter
+            .prefix
+            .as_ref()
+            .or(container.setter.prefix.as_ref());
+        let setter = match (attrs.setter.name, prefix) {
+            (Some(name), _) => name,
+            (None, Some(prefix)) => format_ident!("{}{}", prefix, ident.unraw()),
+            (None, None) => ident.clone(),
+        };
+
+        let vis = match attrs.setter.vis.as_ref().or(container.setter.vis.as_ref()) {
+            Some(vis) => vis.clone(),
+            None => parse_quote!(pub),
+        };
+
+        // Constraints look at whether a field is set, which only makes sense
+        // for fields stored as an Option.
+        if !matches!(kind, Kind::Required | Kind::Optional(_)) {
+            let constraint = attrs
+                .group
+                .iter()
+                .chain(&attrs.requires)
+                .chain(&attrs.conflicts_with)
+                .next();
+            if let Some(constraint) = constraint {
+                return Err(Error::new_spanned(
+                    constraint,
+                    "`group`, `requires` and `conflicts_with` cannot be used on `each`, `sub_builder` or `skip` fields",
+                ));
+            }
+        }
+
+        let cfg: Vec<&Attribute> = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg") || attr.path().is_ident("cfg_attr"))
+            .collect();
+        let predicates = cfg
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .map(|attr| Ok(attr.meta.require_list()?.tokens.clone()))
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let docs = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc") || attr.path().is_ident("deprecated"));
+        let setter_attrs = container.setter.attrs.iter().chain(&attrs.setter.attrs);
+
+        Ok(Field {
+            ident,
+            member,
+            setter,
+            vis,
+            ty,
+            kind,
+            default,
+            into: attrs.setter.into.or(container.setter.into).unwrap_or(false),
+            try_setter: attrs.try_setter.or(container.try_setter).unwrap_or(false),
+            strip_option: attrs
+                .setter
+                .strip_option
+                .or(container.setter.strip_option)
+                .unwrap_or(true),
+            rename: attrs.rename,
+            env: attrs.env.map(|var| Env {
+                var,
+                sep: attrs.env_sep,
+            }),
+            group: attrs.group,
+            exactly_one: attrs.exactly_one.is_some(),
+            requires: attrs.requires,
+            conflicts_with: attrs.conflicts_with,
+            cfg: quote!(#(#cfg)*),
+            cfg_predicate: if predicates.is_empty() {
+                None
+            } else {
+                Some(quote!(all(#(#predicates),*)))
+            },
+            attrs: quote! {
+                #(#cfg)*
+                #(#docs)*
+                #(#[#setter_attrs])*
+            },
+        })
+    }
+
+    /// Field name as written in the struct, or as given by `name` for a field
+    /// of a tuple struct, for use in messages.
+    pub fn name(&self) -> String {
+        self.ident.unraw().to_string()
+    }
+
+    /// Whether `build()` fails when this field was never set.
+    pub fn is_required(&self) -> bool {
+        matches!(self.kind, Kind::Required) && self.default.is_none()
+    }
+
+    /// Expression `build()` falls back to for an unset field with a default.
+    /// The fields declared before this one are in scope as local variables.
+    pub fn default_value(&self) -> Option<TokenStream> {
+        let ty = self.ty;
+        match self.default.as_ref()? {
+            DefaultValue::Trait => Some(quote!(<#ty as ::std::default::Default>::default())),
+            DefaultValue::Expr(expr) => Some(quote!(#expr)),
+        }
+    }
+
+    /// Whether the field is in a group, or requires or conflicts with others.
+    pub fn has_constraints(&self) -> bool {
+        self.group.is_some() || !self.requires.is_empty() || !self.conflicts_with.is_empty()
+    }
+
+    /// Whether the builder has storage and setters for this field.
+    pub fn is_stored(&self) -> bool {
+        !matches!(self.kind, Kind::Skipped)
+    }
+
+    /// Declaration of this field's storage in the builder struct.
+    pub fn storage(&self) -> Option<TokenStream> {
+        let ident = &self.ident;
+        let ty = self.storage_ty();
+        let cfg = &self.cfg;
+        match &self.kind {
+            Kind::Each { .. } => Some(quote!(#cfg #ident: #ty)),
+            Kind::SubBuilder { builder, .. } => Some(quote!(#cfg #ident: #builder)),
+            Kind::Skipped => None,
+            Kind::Required | Kind::Optional(_) => {
+                Some(quote!(#cfg #ident: ::std::option::Option<#ty>))
+            }
+        }
+    }
+
+    /// Initializer of this field's storage in a fresh builder.
+    pub fn init(&self) -> Option<TokenStream> {
+        let ident = &self.ident;
+        let cfg = &self.cfg;
+        match self.kind {
+            Kind::Each { .. } => Some(quote!(#cfg #ident: ::std::default::Default::default())),
+            Kind::SubBuilder { .. } => {
+                let ty = self.ty;
+                Some(quote!(#cfg #ident: <#ty>::builder()))
+            }
+            Kind::Skipped => None,
+            Kind::Required | Kind::Optional(_) => {
+                Some(quote!(#cfg #ident: ::std::option::Option::None))
+            }
+        }
+    }
+
+    /// Type of the value held by the builder while this field is being set.
+    pub fn storage_ty(&self) -> &Type {
+        match &self.kind {
+            Kind::Required => self.ty,
+            Kind::Optional(inner) => inner,
+            Kind::Each { .. } | Kind::SubBuilder { .. } | Kind::Skipped => self.ty,
+        }
+    }
+
+    /// Type taken by the setter of the whole field.
+    pub fn setter_ty(&self) -> &Type {
+        match self.kind {
+            Kind::Optional(_) if !self.strip_option => self.ty,
+            _ => self.storage_ty(),
+        }
+    }
+
+    /// What the setter of the whole field stores, given the `value` it was
+    /// called with after conversion.
+    pub fn setter_store(&self, value: TokenStream) -> TokenStream {
+        match self.kind {
+            Kind::Required => quote!(::std::option::Option::Some(#value)),
+            Kind::Optional(_) if self.strip_option => quote!(::std::option::Option::Some(#value)),
+            Kind::Optional(_) | Kind::Each { .. } | Kind::SubBuilder { .. } | Kind::Skipped => {
+                value
+            }
+        }
+    }
+
+    /// Parameter type of a setter that stores a `ty` taken as argument `arg`,
+    /// and the expression converting that argument into a `ty`.
+    pub fn setter_input(&self, arg: &Ident, ty: &Type) -> (TokenStream, TokenStream) {
+        if self.into {
+            (
+                quote!(impl ::std::convert::Into<#ty>),
+                quote!(::std::convert::Into::into(#arg)),
+            )
+        } else {
+            (quote!(#ty), quote!(#arg))
+        }
+    }
+
+    /// Whether an all-at-once setter should exist next to the one-at-a-time
+    /// `each` setter, which it does unless they have the same name.
+    pub fn has_whole_setter(&self) -> bool {
+        match &self.kind {
+            Kind::Each { setter, .. } => *setter != self.setter,
+            Kind::Skipped => false,
+            _ => true,
+        }
+    }
+}
+
+/// The error variant for a sub-builder field: `current_dir` -> `CurrentDir`,
+/// and a tuple struct's `_0` -> `Field0`.
+fn variant(ident: &Ident) -> Ident {
+    let camel = camel_case(ident);
+    if camel.starts_with(|c: char| c.is_ascii_digit()) {
+        format_ident!("Field{}", camel)
+    } else {
+        format_ident!("{}", camel)
+    }
+}
+
+/// `current_dir` -> `CurrentDir`
+pub fn camel_case(ident: &Ident) -> String {
+    let mut camel = String::new();
+    for word in ident.unraw().to_string().split('_') {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
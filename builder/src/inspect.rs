@@ -0,0 +1,151 @@
+//! Methods for looking into a builder while it is being filled in:
+//! `get_<field>`, `is_<field>_set` and `clear_<field>` for every field it
+//! stores, and `missing_fields`.
+//!
+//! An `each` field counts as set once it holds at least one element.
+//! Sub-builders only get `clear_<field>`, since `<field>_mut` already gives
+//! access to them.
+
+use crate::field::{Field, Kind};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+/// In a typestate builder, whether a required field is set is part of the
+/// builder's type: such fields cannot be cleared, and there is no
+/// `missing_fields` since build() cannot be called until none are.
+pub fn expand(fields: &[Field], typestate: bool) -> TokenStream {
+    let methods = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = field.name();
+        let cfg = &field.cfg;
+        let vis = &field.vis;
+        let get = format_ident!("get_{}", ident.unraw());
+        let is_set = format_ident!("is_{}_set", ident.unraw());
+        let clear = format_ident!("clear_{}", ident.unraw());
+        let get_doc = format!("The value of `{}` if it was set.", name);
+        let is_set_doc = format!("Whether `{}` was set.", name);
+        let clear_doc = format!("Unsets `{}`, as if its setter was never called.", name);
+
+        match &field.kind {
+            Kind::Required | Kind::Optional(_) => {
+                let ty = field.storage_ty();
+                let clear = if typestate && field.is_required() {
+                    TokenStream::new()
+                } else {
+                    quote! {
+                        #[doc = #clear_doc]
+                        #cfg
+                        #vis fn #clear(&mut self) {
+                            self.#ident = ::std::option::Option::None;
+                        }
+                    }
+                };
+                quote! {
+                    #[doc = #get_doc]
+                    #cfg
+                    #vis fn #get(&self) -> ::std::option::Option<&#ty> {
+                        self.#ident.as_ref()
+                    }
+
+                    #[doc = #is_set_doc]
+                    #cfg
+                    #vis fn #is_set(&self) -> bool {
+                        self.#ident.is_some()
+                    }
+
+                    #clear
+                }
+            }
+            // Emptiness is checked by iterating, which any collection that
+            // `each` can extend supports by reference; the bound is
+            // higher-ranked so that one which doesn't only loses these two
+            // methods.
+            Kind::Each { .. } => {
+                let ty = field.ty;
+                let iterable = quote!(for<'__inspect> &'__inspect #ty: ::std::iter::IntoIterator);
+                quote! {
+                    #[doc = #get_doc]
+                    #[allow(private_bounds)]
+                    #cfg
+                    #vis fn #get(&self) -> ::std::option::Option<&#ty>
+                    where
+                        #iterable,
+                    {
+                        if self.#is_set() {
+                            ::std::option::Option::Some(&self.#ident)
+                        } else {
+                            ::std::option::Option::None
+                        }
+                    }
+
+                    #[doc = #is_set_doc]
+                    #[allow(private_bounds)]
+                    #cfg
+                    #vis fn #is_set(&self) -> bool
+                    where
+                        #iterable,
+                    {
+                        ::std::iter::IntoIterator::into_iter(&self.#ident)
+                            .next()
+                            .is_some()
+                    }
+
+                    #[doc = #clear_doc]
+                    #cfg
+                    #vis fn #clear(&mut self) {
+                        self.#ident = ::std::default::Default::default();
+                    }
+                }
+            }
+            Kind::SubBuilder { .. } => {
+                let ty = field.ty;
+                quote! {
+                    #[doc = #clear_doc]
+                    #cfg
+                    #vis fn #clear(&mut self) {
+                        self.#ident = <#ty>::builder();
+                    }
+                }
+            }
+            Kind::Skipped => TokenStream::new(),
+        }
+    });
+
+    let required: Vec<&Field> = fields.iter().filter(|f| f.is_required()).collect();
+    let missing = if required.is_empty() {
+        quote!(::std::vec::Vec::new())
+    } else {
+        let idents = required.iter().map(|field| &field.ident);
+        let names = required.iter().map(|field| field.name());
+        let cfgs = required.iter().map(|field| &field.cfg);
+        quote! {
+            let mut missing = ::std::vec::Vec::new();
+            #(
+                #cfgs
+                {
+                    if self.#idents.is_none() {
+                        missing.push(#names);
+                    }
+                }
+            )*
+            missing
+        }
+    };
+    let missing_fields = if typestate {
+        TokenStream::new()
+    } else {
+        quote! {
+            /// Required fields that are not set yet, in declaration order.
+            pub fn missing_fields(&self) -> ::std::vec::Vec<&'static str> {
+                #missing
+            }
+        }
+    };
+
+    quote! {
+        #(#methods)*
+
+        #missing_fields
+    }
+}
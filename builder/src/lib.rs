@@ -1,8 +1,26 @@
+mod attr;
+mod constant;
+mod constraint;
+mod convert;
+mod env;
+mod error;
+mod expand;
+mod field;
+mod inspect;
+mod merge;
+mod names;
+mod no_std;
+mod serde;
+mod ty;
+mod typestate;
+
 use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(Builder)]
+#[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
-
-    unimplemented!()
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
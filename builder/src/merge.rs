@@ -0,0 +1,123 @@
+use crate::attr::Merge;
+use crate::field::{Field, Kind};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Ident, Type};
+
+/// `merge` and `merge_from`, which layer the fields set in another builder on
+/// top of this one: a set field overrides whatever this builder holds, an
+/// unset one leaves it alone. `each` collections are appended to or replaced
+/// depending on their `merge = "..."`.
+///
+/// Sub-builders are merged recursively, so `merge_from` needs the fields of a
+/// sub-builder's struct to be Clone as well.
+pub fn expand(fields: &[Field]) -> TokenStream {
+    let other = Ident::new("other", Span::mixed_site());
+
+    let moves = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let cfg = &field.cfg;
+        let statements = match &field.kind {
+            Kind::Required | Kind::Optional(_) => quote! {
+                if #other.#ident.is_some() {
+                    self.#ident = #other.#ident;
+                }
+            },
+            Kind::Each { merge, .. } => each(ident, *merge, quote!(#other.#ident)),
+            Kind::SubBuilder { .. } => quote! {
+                self.#ident = self.#ident.merge(#other.#ident);
+            },
+            Kind::Skipped => return TokenStream::new(),
+        };
+        quote!(#cfg { #statements })
+    });
+
+    let copies = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let cfg = &field.cfg;
+        let statements = match &field.kind {
+            Kind::Required | Kind::Optional(_) => quote! {
+                if let ::std::option::Option::Some(value) = &#other.#ident {
+                    self.#ident = ::std::option::Option::Some(::std::clone::Clone::clone(value));
+                }
+            },
+            Kind::Each { merge, .. } => each(
+                ident,
+                *merge,
+                quote!(::std::clone::Clone::clone(&#other.#ident)),
+            ),
+            Kind::SubBuilder { .. } => quote! {
+                self.#ident.merge_from(&#other.#ident);
+            },
+            Kind::Skipped => return TokenStream::new(),
+        };
+        quote!(#cfg { #statements })
+    });
+
+    // Higher-ranked for the same reason as in to_builder: a field that is not
+    // Clone, or a collection that cannot be iterated, only makes the method
+    // uncallable.
+    let iter_bounds: Vec<TokenStream> = fields
+        .iter()
+        .filter_map(|field| match &field.kind {
+            Kind::Each { item, .. } => {
+                let ty = field.ty;
+                let item = item.ty();
+                Some(quote!(for<'__merge> #ty: ::std::iter::IntoIterator<Item = #item>,))
+            }
+            _ => None,
+        })
+        .collect();
+    let clone_bounds = fields.iter().filter_map(|field| -> Option<&Type> {
+        match &field.kind {
+            Kind::Required | Kind::Each { .. } => Some(field.ty),
+            Kind::Optional(inner) => Some(inner),
+            Kind::SubBuilder { .. } | Kind::Skipped => None,
+        }
+    });
+
+    quote! {
+        /// Overrides the fields of this builder with those set in `other`.
+        #[allow(private_bounds)]
+        pub fn merge(mut self, #other: Self) -> Self
+        where
+            #(#iter_bounds)*
+        {
+            #(#moves)*
+            self
+        }
+
+        /// Like [`merge`](Self::merge), but copies the values out of `other`.
+        #[allow(private_bounds)]
+        pub fn merge_from(&mut self, #other: &Self) -> &mut Self
+        where
+            #(#iter_bounds)*
+            #(for<'__merge> #clone_bounds: ::std::clone::Clone,)*
+        {
+            #(#copies)*
+            self
+        }
+    }
+}
+
+/// Merges the elements in `source` into the collection `ident` of `self`.
+/// Replacing only happens when there is at least one new element.
+fn each(ident: &Ident, merge: Merge, source: TokenStream) -> TokenStream {
+    match merge {
+        Merge::Append => quote! {
+            ::std::iter::Extend::extend(&mut self.#ident, #source);
+        },
+        Merge::Replace => {
+            let items = Ident::new("items", Span::mixed_site());
+            quote! {
+                let mut #items = ::std::iter::Iterator::peekable(
+                    ::std::iter::IntoIterator::into_iter(#source),
+                );
+                if #items.peek().is_some() {
+                    self.#ident = ::std::default::Default::default();
+                    ::std::iter::Extend::extend(&mut self.#ident, #items);
+                }
+            }
+        }
+    }
+}
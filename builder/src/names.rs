@@ -0,0 +1,138 @@
+use crate::attr::Errors;
+use crate::env;
+use crate::expand::Target;
+use crate::field::{Field, Item, Kind};
+use proc_macro2::Span;
+use std::collections::HashMap;
+use syn::ext::IdentExt;
+use syn::{Error, Ident, Result};
+
+/// Reports methods of the builder, or variants of its error type, that would
+/// be generated more than once, which renamed or prefixed setters make easy
+/// to run into. Without this the compiler reports them against the derive,
+/// with no hint of which field or attribute is involved.
+pub fn check(target: &Target, fields: &[Field], typestate: bool) -> Result<()> {
+    let mut methods: Vec<(String, Span, String)> = Vec::new();
+    let mut method = |ident: &Ident, what: String| {
+        methods.push((ident.unraw().to_string(), ident.span(), what));
+    };
+
+    method(&target.build, "the build method".to_owned());
+    let mut fixed = vec!["merge", "merge_from"];
+    if !typestate {
+        fixed.push("missing_fields");
+    }
+    if env::any(fields) {
+        fixed.extend(["from_env", "with_env"]);
+    }
+    for name in fixed {
+        method(
+            &Ident::new(name, Span::call_site()),
+            format!("the builder's `{}` method", name),
+        );
+    }
+
+    for field in fields {
+        let name = field.name();
+        let setter = &field.setter;
+        let ident = field.ident.unraw();
+        let span = field.ident.span();
+        if field.is_stored() && !(typestate && field.is_required()) {
+            method(
+                &Ident::new(&format!("clear_{}", ident), span),
+                format!("the clear_ method of `{}`", name),
+            );
+        }
+        if !matches!(field.kind, Kind::SubBuilder { .. } | Kind::Skipped) {
+            method(
+                &Ident::new(&format!("get_{}", ident), span),
+                format!("the getter of `{}`", name),
+            );
+            method(
+                &Ident::new(&format!("is_{}_set", ident), span),
+                format!("the is_set method of `{}`", name),
+            );
+        }
+        if let Kind::SubBuilder { .. } = field.kind {
+            method(setter, format!("the setter of `{}`", name));
+            method(
+                &Ident::new(&format!("{}_mut", field.ident.unraw()), field.ident.span()),
+                format!("the accessor of `{}`", name),
+            );
+            continue;
+        }
+        if field.has_whole_setter() {
+            method(setter, format!("the setter of `{}`", name));
+            if field.try_setter {
+                method(
+                    &Ident::new(&format!("try_{}", setter.unraw()), setter.span()),
+                    format!("the try_ setter of `{}`", name),
+                );
+            }
+        }
+        if let Kind::Each { setter, item, .. } = &field.kind {
+            method(setter, format!("the element setter of `{}`", name));
+            if field.try_setter && matches!(item, Item::One(_)) {
+                method(
+                    &Ident::new(&format!("try_{}", setter.unraw()), setter.span()),
+                    format!("the try_ element setter of `{}`", name),
+                );
+            }
+            method(
+                &Ident::new(
+                    &format!("extend_{}", field.ident.unraw()),
+                    field.ident.span(),
+                ),
+                format!("the extend_ method of `{}`", name),
+            );
+        }
+    }
+
+    let mut errors = Errors::default();
+    report(&methods, &mut errors);
+
+    // Likewise for the variants of the error type wrapping sub-builder
+    // errors, which are named after their field.
+    let mut variants: Vec<(String, Span, String)> = [
+        "UninitializedFields",
+        "ValidationError",
+        "EnvError",
+        "ConstraintViolations",
+    ]
+    .iter()
+    .map(|name| {
+        (
+            (*name).to_owned(),
+            Span::call_site(),
+            format!("the error's `{}` variant", name),
+        )
+    })
+    .collect();
+    for field in fields {
+        if let Kind::SubBuilder { variant, .. } = &field.kind {
+            variants.push((
+                variant.to_string(),
+                field.ident.span(),
+                format!("the error variant of `{}`", field.name()),
+            ));
+        }
+    }
+    report(&variants, &mut errors);
+
+    errors.finish()
+}
+
+/// Reports every name in `items` that was already taken by an earlier one.
+fn report(items: &[(String, Span, String)], errors: &mut Errors) {
+    let mut seen: HashMap<&str, &str> = HashMap::new();
+    for (name, span, what) in items {
+        if let Some(first) = seen.get(name.as_str()) {
+            errors.push(Error::new(
+                *span,
+                format!("{} is named `{}`, which is already {}", what, name, first),
+            ));
+        } else {
+            seen.insert(name, what);
+        }
+    }
+}
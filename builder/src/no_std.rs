@@ -0,0 +1,65 @@
+//! `#[builder(no_std)]`: the generated code is written against std, always
+//! through absolute `::std::...` paths, and those paths are rewritten to
+//! `::core` or `::alloc` afterwards. The crate deriving the builder needs
+//! `extern crate alloc;`.
+//!
+//! The error type still implements `Error`, which lives in `core` as well.
+
+use proc_macro2::{Group, Ident, Spacing, TokenStream, TokenTree};
+
+/// Modules and macros of std that are re-exports from `alloc`. Everything
+/// else the generated code uses is in `core`.
+const ALLOC: &[&str] = &["borrow", "boxed", "format", "string", "vec"];
+
+pub fn rewrite(tokens: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut rewritten = Vec::with_capacity(tokens.len());
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => {
+                let mut copy = Group::new(group.delimiter(), rewrite(group.stream()));
+                copy.set_span(group.span());
+                rewritten.push(TokenTree::Group(copy));
+            }
+            TokenTree::Ident(ident) if ident == "std" && is_absolute(&tokens[..i]) => {
+                let krate = match tokens.get(i + 3) {
+                    Some(TokenTree::Ident(module)) if ALLOC.iter().any(|m| module == m) => "alloc",
+                    _ => "core",
+                };
+                rewritten.push(TokenTree::Ident(Ident::new(krate, ident.span())));
+            }
+            _ => rewritten.push(token.clone()),
+        }
+    }
+    rewritten.into_iter().collect()
+}
+
+/// Whether the tokens before a path segment end in a leading `::`, as opposed
+/// to the `::` between two segments.
+fn is_absolute(before: &[TokenTree]) -> bool {
+    match before {
+        [.., prev, TokenTree::Punct(first), TokenTree::Punct(second)] => {
+            first.as_char() == ':'
+                && first.spacing() == Spacing::Joint
+                && second.as_char() == ':'
+                && !is_segment_end(prev)
+        }
+        [TokenTree::Punct(first), TokenTree::Punct(second)] => {
+            first.as_char() == ':' && first.spacing() == Spacing::Joint && second.as_char() == ':'
+        }
+        _ => false,
+    }
+}
+
+/// Whether `token` can be the end of a path segment, like `a` in `a::std`,
+/// as opposed to a keyword like `return` or `impl` before a path.
+fn is_segment_end(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Ident(ident) => {
+            let name = ident.to_string();
+            syn::parse_str::<syn::Ident>(&name).is_ok()
+                || ["crate", "self", "super", "Self"].contains(&name.as_str())
+        }
+        TokenTree::Group(_) | TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+    }
+}
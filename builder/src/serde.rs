@@ -0,0 +1,42 @@
+//! `#[builder(serde)]`: the builder derives `Serialize` and `Deserialize`, so
+//! that a partially filled builder can be loaded from a config file and
+//! finished with setters. Every field may be left out; missing fields stay
+//! unset and get their usual treatment in `build()`.
+
+use crate::field::{Field, Kind};
+use crate::ty;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Attributes to put on the builder struct.
+pub fn derive() -> TokenStream {
+    quote! {
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+    }
+}
+
+/// Attributes to put on the builder's storage for `field`.
+pub fn field(field: &Field) -> TokenStream {
+    let skip = match &field.kind {
+        Kind::Required | Kind::Optional(_) => {
+            quote!(skip_serializing_if = "::core::option::Option::is_none")
+        }
+        // Only the std collections are known to have an `is_empty`.
+        Kind::Each { .. } => match ty::collection(field.ty) {
+            Some(_) => {
+                let ty = field.ty;
+                let is_empty = quote!(<#ty>::is_empty).to_string();
+                quote!(skip_serializing_if = #is_empty)
+            }
+            None => TokenStream::new(),
+        },
+        Kind::SubBuilder { .. } | Kind::Skipped => TokenStream::new(),
+    };
+    let rename = field
+        .rename
+        .as_ref()
+        .map(|rename| quote!(rename = #rename,));
+    quote! {
+        #[serde(default, #rename #skip)]
+    }
+}
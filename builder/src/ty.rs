@@ -0,0 +1,124 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::format_ident;
+use quote::ToTokens;
+use syn::{GenericArgument, Ident, Path, PathArguments, Type};
+
+/// If `ty` is written as `Option<T>`, `std::option::Option<T>` or
+/// `core::option::Option<T>`, with or without a leading `::`, returns `T`.
+///
+/// Only these token shapes are recognized: the macro runs before name
+/// resolution, so there is no way to tell what an alias like `Maybe<T>` will
+/// end up referring to. Such fields can be marked `#[builder(optional)]`.
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return None,
+    };
+    let names: Vec<String> = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let qualified = matches!(
+        names.as_slice(),
+        [krate, module, _] if (krate == "std" || krate == "core") && module == "option"
+    );
+    let plain = names.len() == 1 && path.leading_colon.is_none();
+    if !(plain || qualified) || names.last()? != "Option" {
+        return None;
+    }
+    only_arg(ty)
+}
+
+/// If the last segment of `ty` has exactly one generic argument, which is a
+/// type, returns it: `T` for `Maybe<T>`.
+pub fn only_arg(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last()?,
+        _ => return None,
+    };
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(bracketed) => &bracketed.args,
+        _ => return None,
+    };
+    if args.len() != 1 {
+        return None;
+    }
+    match &args[0] {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Collections from std that `each` knows the element type of.
+const SEQUENCES: &[&str] = &[
+    "Vec",
+    "VecDeque",
+    "LinkedList",
+    "BinaryHeap",
+    "HashSet",
+    "BTreeSet",
+];
+const MAPS: &[&str] = &["HashMap", "BTreeMap"];
+
+/// Element type of a std collection.
+pub enum Collection<'a> {
+    /// `Vec<T>`, `HashSet<T>`, ...
+    Sequence(&'a Type),
+    /// `HashMap<K, V>` or `BTreeMap<K, V>`, extended with `(K, V)`.
+    Map(&'a Type, &'a Type),
+}
+
+/// If `ty` is one of the std collections, by the name of its last path
+/// segment, returns what it is extended with. Trailing arguments such as the
+/// hasher of a `HashMap` are ignored.
+pub fn collection(ty: &Type) -> Option<Collection<'_>> {
+    let segment = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last()?,
+        _ => return None,
+    };
+    let mut args = match &segment.arguments {
+        PathArguments::AngleBracketed(bracketed) => {
+            bracketed.args.iter().filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+        }
+        _ => return None,
+    };
+    let name = segment.ident.to_string();
+    if SEQUENCES.contains(&name.as_str()) {
+        Some(Collection::Sequence(args.next()?))
+    } else if MAPS.contains(&name.as_str()) {
+        Some(Collection::Map(args.next()?, args.next()?))
+    } else {
+        None
+    }
+}
+
+/// Whether `ty` refers to any of the given generic type parameters, anywhere
+/// within it.
+pub fn mentions(ty: &Type, params: &[&Ident]) -> bool {
+    fn walk(tokens: TokenStream, params: &[&Ident]) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            TokenTree::Ident(ident) => params.iter().any(|param| **param == ident),
+            TokenTree::Group(group) => walk(group.stream(), params),
+            _ => false,
+        })
+    }
+    !params.is_empty() && walk(ty.to_token_stream(), params)
+}
+
+/// `path` with `suffix` appended to the name of its last segment, optionally
+/// dropping that segment's generic arguments: `a::Foo<T>` becomes
+/// `a::FooBuilder<T>` or `a::FooBuilder`.
+pub fn suffixed(path: &Path, suffix: &str, keep_args: bool) -> Path {
+    let mut path = path.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.ident = format_ident!("{}{}", last.ident, suffix);
+        if !keep_args {
+            last.arguments = PathArguments::None;
+        }
+    }
+    path
+}
@@ -0,0 +1,275 @@
+//! `#[builder(typestate)]`: the builder carries one type parameter per
+//! required field recording whether that field has been set, and `build()` is
+//! only callable once all of them are `Set`. Forgetting a required field is
+//! then a compile error instead of an `Err` at runtime.
+
+use crate::attr::{self, Pattern};
+use crate::expand::Target;
+use crate::field::{camel_case, Field, Kind};
+use crate::{convert, expand, inspect, merge};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_quote, DeriveInput, GenericParam, Ident};
+
+pub fn expand(
+    input: &DeriveInput,
+    container: &attr::Container,
+    target: &Target,
+    fields: &[Field],
+) -> TokenStream {
+    let vis = &input.vis;
+    let name = target.name;
+    let builder = &target.builder;
+    let build = &target.build;
+    let build_vis = &target.build_vis;
+    let ctor = &target.ctor;
+    let path = target.path();
+    let state = format_ident!("__{}State", builder);
+
+    let required: Vec<&Field> = fields.iter().filter(|f| f.is_required()).collect();
+    let params: Vec<Ident> = required
+        .iter()
+        .map(|field| format_ident!("__{}", camel_case(&field.ident)))
+        .collect();
+    let traits: Vec<Ident> = required
+        .iter()
+        .map(|field| format_ident!("Has{}", camel_case(&field.ident)))
+        .collect();
+    // A required field that is cfg'd away has no setter, so its parameter
+    // starts out `Set` whenever the field does not exist.
+    let initial: Vec<Option<Ident>> = required
+        .iter()
+        .map(|field| {
+            field
+                .cfg_predicate
+                .as_ref()
+                .map(|_| format_ident!("Initial{}", camel_case(&field.ident)))
+        })
+        .collect();
+    let start = initial.iter().map(|initial| match initial {
+        Some(initial) => quote!(#state::#initial),
+        None => quote!(#state::Unset),
+    });
+
+    // The builder is generic over the struct's own parameters followed by the
+    // state parameters. Defaults are dropped since they would have to be
+    // trailing.
+    let (orig_impl, orig_ty, orig_where) = input.generics.split_for_impl();
+    let args: Vec<TokenStream> = input
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        })
+        .collect();
+    let mut generics = input.generics.clone();
+    for param in &mut generics.params {
+        match param {
+            GenericParam::Type(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Const(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+    generics.params.extend(
+        params
+            .iter()
+            .map(|param| -> GenericParam { parse_quote!(#param) }),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let markers = required.iter().zip(&traits).map(|(field, tr)| {
+        let message = format!("`{}` is missing required field `{}`", builder, field.name());
+        let label = format!("call `.{}(...)` before `.{}()`", field.setter, build);
+        quote! {
+            #[diagnostic::on_unimplemented(message = #message, label = #label)]
+            pub trait #tr {}
+            impl #tr for Set {}
+        }
+    });
+    let initial_states = required
+        .iter()
+        .zip(&initial)
+        .filter_map(|(field, initial)| {
+            let initial = initial.as_ref()?;
+            let predicate = field.cfg_predicate.as_ref()?;
+            Some(quote! {
+                #[cfg(#predicate)]
+                pub type #initial = Unset;
+                #[cfg(not(#predicate))]
+                pub type #initial = Set;
+            })
+        });
+
+    let struct_attrs = &container.struct_attrs;
+    let storage = fields.iter().filter_map(Field::storage);
+    let merge = merge::expand(fields);
+    let inspect = inspect::expand(fields, true);
+    let init = fields.iter().filter_map(Field::init);
+    let marker = expand::marker(input, fields).map(|marker| quote!(#marker,));
+    let marker_init = marker
+        .as_ref()
+        .map(|_| quote!(__marker: ::std::marker::PhantomData,));
+
+    let setters = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let setter = &field.setter;
+        let vis = &field.vis;
+        let attrs = &field.attrs;
+        let ty = field.setter_ty();
+        let (input, value) = field.setter_input(ident, ty);
+        let receiver = quote!(self);
+        match &field.kind {
+            Kind::Required if field.is_required() => {
+                // Setting a required field moves the builder into a state
+                // whose parameter for that field is `Set`.
+                let position = required.iter().position(|f| f.ident == *ident).unwrap();
+                let after = params.iter().enumerate().map(|(i, param)| {
+                    if i == position {
+                        quote!(#state::Set)
+                    } else {
+                        quote!(#param)
+                    }
+                });
+                let moved = fields.iter().filter(|f| f.is_stored()).map(|f| {
+                    let other = &f.ident;
+                    let cfg = &f.cfg;
+                    if other == ident {
+                        quote!(#ident: ::std::option::Option::Some(#value))
+                    } else {
+                        quote!(#cfg #other: self.#other)
+                    }
+                });
+                let ret = quote!(#builder<#(#args,)* #(#after),*>);
+                let try_setter = expand::try_setter(field, setter, ty, &receiver, &ret);
+                quote! {
+                    #attrs
+                    #vis fn #setter(self, #ident: #input) -> #ret {
+                        #builder {
+                            #(#moved,)*
+                            #marker_init
+                            __typestate: ::std::marker::PhantomData,
+                        }
+                    }
+
+                    #try_setter
+                }
+            }
+            Kind::Required | Kind::Optional(_) => {
+                let store = field.setter_store(value);
+                let try_setter = expand::try_setter(field, setter, ty, &receiver, &quote!(Self));
+                quote! {
+                    #attrs
+                    #vis fn #setter(mut self, #ident: #input) -> Self {
+                        self.#ident = #store;
+                        self
+                    }
+
+                    #try_setter
+                }
+            }
+            Kind::Each { .. } => {
+                let whole = if field.has_whole_setter() {
+                    let try_setter =
+                        expand::try_setter(field, setter, ty, &receiver, &quote!(Self));
+                    quote! {
+                        #attrs
+                        #vis fn #setter(mut self, #ident: #input) -> Self {
+                            self.#ident = #value;
+                            self
+                        }
+
+                        #try_setter
+                    }
+                } else {
+                    TokenStream::new()
+                };
+                let each = expand::each_setters(field, Pattern::Owned);
+                quote! {
+                    #whole
+                    #each
+                }
+            }
+            // Rejected before getting here.
+            Kind::SubBuilder { .. } => TokenStream::new(),
+            Kind::Skipped => TokenStream::new(),
+        }
+    });
+
+    // Only `Set` implements the marker traits, so required fields are there.
+    // Sub-builders are rejected up front, so there is no error to wrap.
+    let resolve = expand::resolve(fields, Pattern::Owned, &format_ident!("{}Error", builder));
+    let members = fields.iter().map(|field| &field.member);
+    let idents = fields.iter().map(|field| &field.ident);
+    let cfgs = fields.iter().map(|field| &field.cfg);
+    let set = required.iter().map(|_| quote!(#state::Set));
+    let convert = match target.variant {
+        Some(_) => TokenStream::new(),
+        None => convert::expand(
+            input,
+            &quote!(#builder<#(#args,)* #(#set),*>),
+            fields,
+            &quote!(#marker_init __typestate: ::std::marker::PhantomData,),
+        ),
+    };
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        #vis mod #state {
+            pub struct Set;
+            pub struct Unset;
+            #(#markers)*
+            #(#initial_states)*
+        }
+
+        #(#[#struct_attrs])*
+        #vis struct #builder #impl_generics #where_clause {
+            #(#storage,)*
+            #marker
+            __typestate: ::std::marker::PhantomData<(#(#params,)*)>,
+        }
+
+        impl #orig_impl #name #orig_ty #orig_where {
+            pub fn #ctor() -> #builder<#(#args,)* #(#start),*> {
+                #builder {
+                    #(#init,)*
+                    #marker_init
+                    __typestate: ::std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            #inspect
+
+            #merge
+
+            // Bounding the method rather than the impl keeps `build` visible in
+            // every state, so calling it too early reports the missing field
+            // through the marker trait's diagnostic.
+            #[allow(deprecated)]
+            #build_vis fn #build(self) -> #name #orig_ty
+            where
+                #(#params: #state::#traits,)*
+            {
+                #resolve
+                #path {
+                    #(#cfgs #members: #idents,)*
+                }
+            }
+        }
+
+        #convert
+    }
+}
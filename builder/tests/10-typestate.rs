@@ -0,0 +1,42 @@
+// With #[builder(typestate)] on the struct, the builder tracks in its type
+// which required fields have been set so far. Setters consume the builder and
+// return it in its new state, and `build()` returns the struct directly rather
+// than a Result because there is nothing left that could be missing.
+//
+// Option fields and `each` fields remain optional and can be set in any state,
+// any number of times.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .executable("cargo".to_owned())
+        .arg("--release".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert!(command.env.is_empty());
+    assert!(command.current_dir.is_none());
+
+    let command = Command::builder()
+        .current_dir("..".to_owned())
+        .executable("rustc".to_owned())
+        .executable("cargo".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+}
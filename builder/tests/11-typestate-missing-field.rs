@@ -0,0 +1,15 @@
+// A typestate builder whose required fields have not all been set has no
+// usable `build()`. The error should name the field that is missing.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let _command = Command::builder().current_dir("..".to_owned()).build();
+}
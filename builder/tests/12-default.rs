@@ -0,0 +1,52 @@
+// Fields marked #[builder(default)] may be left unset, in which case build()
+// fills them in with Default::default(). With #[builder(default = "...")] the
+// string is an arbitrary expression evaluated instead. The expression may refer
+// to any field declared above it by name, already holding its final value.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(default)]
+    args: Vec<String>,
+    #[builder(default = "vec![format!(\"PROGRAM={}\", executable)]")]
+    env: Vec<String>,
+    #[builder(default = "Some(\"..\".to_owned())")]
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+
+    assert!(command.args.is_empty());
+    assert_eq!(command.env, vec!["PROGRAM=cargo"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .args(vec!["build".to_owned()])
+        .env(vec![])
+        .current_dir("/".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build"]);
+    assert!(command.env.is_empty());
+    assert_eq!(command.current_dir.as_deref(), Some("/"));
+
+    // A field with a default is not required, even by a typestate builder.
+    #[derive(Builder)]
+    #[builder(typestate)]
+    pub struct Job {
+        name: String,
+        #[builder(default = "1")]
+        retries: u32,
+    }
+
+    let job = Job::builder().name("nightly".to_owned()).build();
+    assert_eq!(job.retries, 1);
+}
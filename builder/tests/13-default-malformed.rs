@@ -0,0 +1,19 @@
+// The value of `default` must be a string literal containing a valid Rust
+// expression. Anything else should be reported at the offending tokens.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(default = 1)]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Job {
+    #[builder(default = "1 +")]
+    retries: u32,
+}
+
+fn main() {}
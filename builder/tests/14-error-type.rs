@@ -0,0 +1,57 @@
+// build() returns a dedicated error type named after the builder rather than
+// Box<dyn Error>, so that callers can find out what went wrong without
+// inspecting strings. All missing fields are reported together.
+//
+//     pub enum CommandBuilderError {
+//         UninitializedFields(Vec<&'static str>),
+//         ValidationError(String),
+//     }
+//
+// The error implements std::error::Error and Display, so it still converts
+// into Box<dyn Error> through the question mark operator.
+
+use derive_builder::Builder;
+use std::error::Error;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: String,
+    stdin: Option<String>,
+}
+
+fn build_boxed() -> Result<Command, Box<dyn Error>> {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .env(vec![])
+        .current_dir("..".to_owned())
+        .build()?;
+    Ok(command)
+}
+
+fn main() {
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::UninitializedFields(vec!["env", "current_dir"]),
+    );
+    assert_eq!(err.to_string(), "missing fields `env`, `current_dir`");
+
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .env(vec![])
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "missing field `current_dir`");
+
+    let command = build_boxed().unwrap();
+    assert_eq!(command.executable, "cargo");
+}
@@ -0,0 +1,81 @@
+// Structs with lifetime, type and const parameters, with bounds given inline or
+// in a where-clause. The builder carries the same generics as the struct, so
+// borrowed fields like &'a str can be set without allocating.
+//
+//     pub struct RequestBuilder<'a, B: Body, const N: usize>
+//     where
+//         B: Clone,
+//     {
+//         ...
+//     }
+
+use derive_builder::Builder;
+
+pub trait Body {
+    fn len(&self) -> usize;
+}
+
+#[derive(Clone)]
+pub struct Json(String);
+
+impl Body for Json {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[derive(Builder)]
+pub struct Request<'a, B: Body, const N: usize>
+where
+    B: Clone,
+{
+    method: &'a str,
+    #[builder(each = "header")]
+    headers: Vec<(&'a str, &'a str)>,
+    body: Option<B>,
+    tag: [u8; N],
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Response<'a, B, const N: usize = 4>
+where
+    B: Body,
+{
+    status: u16,
+    reason: &'a str,
+    body: B,
+    tag: Option<[u8; N]>,
+}
+
+fn main() {
+    let arena = String::from("POST content-type application/json");
+    let mut words = arena.split(' ');
+    let method = words.next().unwrap();
+    let name = words.next().unwrap();
+    let value = words.next().unwrap();
+
+    let request: Request<Json, 2> = Request::builder()
+        .method(method)
+        .header((name, value))
+        .body(Json("{}".to_owned()))
+        .tag([1, 2])
+        .build()
+        .unwrap();
+
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.headers, vec![("content-type", "application/json")]);
+    assert_eq!(request.body.unwrap().len(), 2);
+    assert_eq!(request.tag, [1, 2]);
+
+    let response: Response<Json> = Response::builder()
+        .reason(&arena[..4])
+        .status(200)
+        .body(Json("null".to_owned()))
+        .build();
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.reason, "POST");
+    assert_eq!(response.body.len(), 4);
+    assert!(response.tag.is_none());
+}
@@ -0,0 +1,58 @@
+// With #[builder(setter(into))] a setter accepts any value convertible into the
+// field's type, so callers can pass "cargo" where a String is expected. The
+// option applies to one field when given on that field, or to every field when
+// given on the struct, in which case an individual field can still opt out
+// with #[builder(setter(into = false))].
+//
+// Element setters generated by `each` accept `impl Into` of the element type.
+//
+//     impl CommandBuilder {
+//         pub fn executable(&mut self, executable: impl Into<String>) -> &mut Self {
+//             self.executable = Some(executable.into());
+//             self
+//         }
+//
+//         ...
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(setter(into))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(setter(into = false))]
+    timeout: Option<u64>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Job {
+    #[builder(setter(into))]
+    name: String,
+    #[builder(each = "tag", setter(into))]
+    tags: Vec<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg(String::from("--release"))
+        .current_dir("..")
+        .timeout(30)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.timeout, Some(30));
+
+    let job = Job::builder().tag("ci").name("nightly").build();
+    assert_eq!(job.name, "nightly");
+    assert_eq!(job.tags, vec!["ci"]);
+}
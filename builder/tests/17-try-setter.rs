@@ -0,0 +1,64 @@
+// #[builder(try_setter)] on a field, or on the struct to cover every field,
+// generates a fallible `try_` setter next to each ordinary setter. It accepts
+// anything with a TryInto conversion to the field's type and returns the
+// conversion error instead of storing a value.
+//
+//     impl ServerBuilder {
+//         pub fn try_port<V: TryInto<u16>>(
+//             &mut self,
+//             port: V,
+//         ) -> Result<&mut Self, <V as TryInto<u16>>::Error> {
+//             ...
+//         }
+//     }
+//
+// For an Option field the converted value is wrapped in Some, and for an
+// `each` field the element setter gets a `try_` variant as well.
+
+use derive_builder::Builder;
+use std::num::TryFromIntError;
+
+#[derive(Builder)]
+#[builder(try_setter)]
+pub struct Server {
+    port: u16,
+    backlog: Option<u8>,
+    #[builder(each = "worker")]
+    workers: Vec<u8>,
+    #[builder(try_setter = false)]
+    name: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Listener {
+    #[builder(try_setter)]
+    port: u16,
+}
+
+fn configure(port: u64) -> Result<Server, Box<dyn std::error::Error>> {
+    let server = Server::builder()
+        .try_port(port)?
+        .try_backlog(128u32)?
+        .try_worker(1i64)?
+        .try_worker(2i64)?
+        .name("api".to_owned())
+        .build()?;
+    Ok(server)
+}
+
+fn main() {
+    let server = configure(8080).unwrap();
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.backlog, Some(128));
+    assert_eq!(server.workers, vec![1, 2]);
+
+    assert!(configure(70000).is_err());
+
+    let mut builder = Server::builder();
+    let err: TryFromIntError = builder.try_backlog(1000).err().unwrap();
+    let _ = err;
+
+    let listener = Listener::builder().try_port(443u32).unwrap().build();
+    assert_eq!(listener.port, 443);
+}
@@ -0,0 +1,69 @@
+// Cross-field invariants are checked by a user-provided function named in
+// #[builder(validate = "path::to::fn")]. It is called by build() with a
+// reference to the constructed struct. With #[builder(validate(builder =
+// "..."))] a function is instead called with the builder itself before any
+// field is resolved, which also sees fields that are still unset.
+//
+// Either function returns Result<(), E> for any E: Display. An Err is reported
+// as CommandBuilderError::ValidationError holding the message.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(validate = "checks::manifest_path_needs_dir")]
+#[builder(validate(builder = "checks::executable_not_empty"))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+mod checks {
+    use super::{Command, CommandBuilder};
+
+    pub fn manifest_path_needs_dir(command: &Command) -> Result<(), String> {
+        if command.args.iter().any(|arg| arg == "--manifest-path") && command.current_dir.is_none()
+        {
+            return Err("`current_dir` must be set when passing --manifest-path".to_owned());
+        }
+        Ok(())
+    }
+
+    pub fn executable_not_empty(builder: &CommandBuilder) -> Result<(), &'static str> {
+        match builder.executable.as_deref() {
+            Some("") => Err("executable must not be empty"),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn main() {
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("--manifest-path".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::ValidationError(
+            "`current_dir` must be set when passing --manifest-path".to_owned(),
+        ),
+    );
+
+    let err = Command::builder()
+        .executable(String::new())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "executable must not be empty");
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("--manifest-path".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["--manifest-path"]);
+}
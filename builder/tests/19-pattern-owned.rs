@@ -0,0 +1,42 @@
+// With #[builder(pattern = "owned")] setters take the builder by value and
+// return it, and build() consumes the builder, moving the fields out of it
+// instead of cloning them. Field types therefore do not need to be Clone.
+//
+//     impl CommandBuilder {
+//         pub fn executable(mut self, executable: String) -> Self { ... }
+//         pub fn build(self) -> Result<Command, CommandBuilderError> { ... }
+//     }
+
+use derive_builder::Builder;
+
+// Deliberately not Clone.
+pub struct Stdin(Vec<u8>);
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    stdin: Option<Stdin>,
+}
+
+fn spawn(command: Command) -> usize {
+    command.args.len() + command.stdin.map_or(0, |stdin| stdin.0.len())
+}
+
+fn main() {
+    let n = spawn(
+        Command::builder()
+            .executable("cat".to_owned())
+            .arg("-".to_owned())
+            .stdin(Stdin(b"hello".to_vec()))
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(n, 6);
+
+    let builder = Command::builder().arg("-n".to_owned());
+    let err = builder.build().err().unwrap();
+    assert_eq!(err, CommandBuilderError::UninitializedFields(vec!["executable"]));
+}
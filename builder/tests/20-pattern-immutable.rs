@@ -0,0 +1,44 @@
+// With #[builder(pattern = "immutable")] setters take &self and return a
+// modified copy of the builder, leaving the original untouched. This makes it
+// easy to fork a partially configured builder into several variations. The
+// builder derives Clone, unless it already does through struct_attrs, and
+// build() takes &self and clones the fields.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable", struct_attrs(derive(Clone, Debug)))]
+pub struct Job {
+    name: String,
+}
+
+fn main() {
+    let base = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned());
+
+    let debug = base.build().unwrap();
+    let release = base.arg("--release".to_owned()).build().unwrap();
+    let elsewhere = base.current_dir("..".to_owned());
+
+    assert_eq!(debug.args, vec!["build"]);
+    assert_eq!(release.args, vec!["build", "--release"]);
+    assert!(base.build().unwrap().current_dir.is_none());
+    assert_eq!(elsewhere.build().unwrap().current_dir.as_deref(), Some(".."));
+
+    let copy = base.clone();
+    assert_eq!(copy.build().unwrap().executable, "cargo");
+
+    let job = Job::builder().name("nightly".to_owned());
+    assert!(format!("{:?}", job.clone()).contains("nightly"));
+    assert_eq!(job.build().unwrap().name, "nightly");
+}
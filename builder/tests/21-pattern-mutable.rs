@@ -0,0 +1,28 @@
+// #[builder(pattern = "mutable")] spells out the default: setters take and
+// return &mut Self, and build() borrows the builder and clones the fields out
+// of it, so the same builder can build more than once.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "mutable", try_setter)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    nice: Option<i8>,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+    let first = builder.build().unwrap();
+
+    builder.arg("--release".to_owned()).try_nice(5i64).unwrap();
+    let second = builder.build().unwrap();
+
+    assert_eq!(first.args, vec!["build"]);
+    assert_eq!(first.nice, None);
+    assert_eq!(second.args, vec!["build", "--release"]);
+    assert_eq!(second.nice, Some(5));
+}
@@ -0,0 +1,18 @@
+// Unknown patterns are rejected, as is asking a typestate builder for anything
+// but the owned pattern its setters are built on.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "borrowed")]
+pub struct Command {
+    executable: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate, pattern = "mutable")]
+pub struct Job {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,92 @@
+// A field whose type has its own derived builder can be marked
+// #[builder(sub_builder)]. The outer builder then holds that type's builder
+// instead of a finished value, and exposes it in two ways:
+//
+//     impl CommandBuilder {
+//         // Configure the nested builder in place.
+//         pub fn sandbox(
+//             &mut self,
+//             configure: impl FnOnce(&mut SandboxConfigBuilder) -> &mut SandboxConfigBuilder,
+//         ) -> &mut Self;
+//
+//         // Borrow the nested builder.
+//         pub fn sandbox_mut(&mut self) -> &mut SandboxConfigBuilder;
+//     }
+//
+// The outer build() builds the nested value too. If that fails, its error is
+// wrapped in a variant of the outer error named after the field, and the
+// message names the nested field by its full path.
+//
+// The builder is assumed to be `<Type>Builder` in the same module as the type.
+// Otherwise it can be named explicitly with #[builder(sub_builder = "...")].
+// Its build method is assumed to be `build`; one renamed with `build_fn` is
+// given as #[builder(sub_builder(build_fn = "..."))], along with the builder
+// as `builder = "..."` if needed.
+//
+// The nested builder must use the default mutable pattern, and the outer one
+// the mutable or owned pattern, see 44-sub-builder-pattern.rs.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct SandboxConfig {
+    net: bool,
+    fs_ro: bool,
+}
+
+mod limits {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Limits {
+        pub memory: u64,
+        pub cpus: Option<u32>,
+    }
+}
+
+#[derive(Builder)]
+#[builder(build_fn(name = "finish"))]
+pub struct Retry {
+    attempts: u32,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(sub_builder)]
+    sandbox: SandboxConfig,
+    #[builder(sub_builder = "limits::LimitsBuilder")]
+    limits: limits::Limits,
+    #[builder(sub_builder(build_fn = "finish"))]
+    retry: Retry,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder
+        .executable("cargo".to_owned())
+        .sandbox(|b| b.net(false).fs_ro(true));
+    builder.limits_mut().memory(1 << 30);
+    builder.retry(|b| b.attempts(3));
+
+    let command = builder.build().unwrap();
+    assert!(!command.sandbox.net);
+    assert!(command.sandbox.fs_ro);
+    assert_eq!(command.limits.memory, 1 << 30);
+    assert_eq!(command.limits.cpus, None);
+    assert_eq!(command.retry.attempts, 3);
+
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .sandbox(|b| b.fs_ro(true))
+        .retry(|b| b.attempts(1))
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::Sandbox(SandboxConfigBuilderError::UninitializedFields(vec!["net"])),
+    );
+    assert_eq!(err.to_string(), "missing field `sandbox.net`");
+    assert!(std::error::Error::source(&err).is_some());
+}
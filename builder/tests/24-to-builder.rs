@@ -0,0 +1,87 @@
+// A finished struct can be turned back into a builder, to produce a variation
+// of it that differs in only a few fields.
+//
+//     impl From<Command> for CommandBuilder { ... }
+//
+//     impl Command {
+//         pub fn to_builder(&self) -> CommandBuilder { ... }
+//     }
+//
+// Every field of the new builder is set, and `each` collections start out with
+// the existing elements. to_builder clones the fields, so it is only callable
+// when all of them are Clone, but a struct with other fields still derives
+// Builder and gets the From impl.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    sandbox: Sandbox,
+}
+
+#[derive(Builder, Clone)]
+pub struct Sandbox {
+    net: bool,
+}
+
+// Not Clone.
+pub struct Pipe;
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Spawn {
+    command: String,
+    stdin: Option<Pipe>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Job {
+    name: String,
+    retries: Option<u32>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .sandbox(|b| b.net(false))
+        .build()
+        .unwrap();
+
+    let release = command
+        .to_builder()
+        .arg("--release".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(release.executable, "cargo");
+    assert_eq!(release.args, vec!["build", "--release"]);
+    assert_eq!(release.current_dir.as_deref(), Some(".."));
+    assert!(!release.sandbox.net);
+
+    let mut builder = CommandBuilder::from(command);
+    builder.sandbox_mut().net(true);
+    assert!(builder.build().unwrap().sandbox.net);
+
+    let spawn = Spawn::builder()
+        .command("cat".to_owned())
+        .stdin(Pipe)
+        .build()
+        .unwrap();
+    let spawn = SpawnBuilder::from(spawn).command("tac".to_owned()).build().unwrap();
+    assert_eq!(spawn.command, "tac");
+    assert!(spawn.stdin.is_some());
+
+    // A typestate builder made from a finished value has every field set, so
+    // it can be built again right away.
+    let job = Job::builder().name("nightly".to_owned()).build();
+    let job = job.to_builder().retries(3).build();
+    assert_eq!(job.name, "nightly");
+    assert_eq!(job.retries, Some(3));
+}
@@ -0,0 +1,98 @@
+// Configuration often comes from several layers: built-in defaults, a file,
+// the environment, the command line. Each layer fills in a builder of its own,
+// and the builders are then merged in order of precedence.
+//
+//     impl CommandBuilder {
+//         pub fn merge(self, other: CommandBuilder) -> CommandBuilder { ... }
+//         pub fn merge_from(&mut self, other: &CommandBuilder) -> &mut CommandBuilder { ... }
+//     }
+//
+// Fields set in `other` override those of `self`, and fields left unset in
+// `other` keep their current value. Collections built up through `each` are
+// appended by default; `merge = "replace"` makes a non-empty collection in
+// `other` replace the existing one instead. Setting `merge` on the struct
+// changes the default for all of its `each` fields.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env", merge = "replace")]
+    env: Vec<(String, String)>,
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    sandbox: Sandbox,
+}
+
+#[derive(Builder, Debug)]
+pub struct Sandbox {
+    net: bool,
+    #[builder(default)]
+    memory: u64,
+}
+
+#[derive(Builder)]
+#[builder(merge = "replace")]
+pub struct Layers {
+    #[builder(each = "layer")]
+    layers: Vec<u32>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Job {
+    name: String,
+    retries: Option<u32>,
+}
+
+fn main() {
+    let mut defaults = Command::builder();
+    defaults
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .env(("RUST_LOG".to_owned(), "info".to_owned()))
+        .sandbox(|b| b.net(false).memory(512));
+    let mut file = Command::builder();
+    file.current_dir("/src".to_owned())
+        .env(("RUST_LOG".to_owned(), "debug".to_owned()));
+    let mut cli = Command::builder();
+    cli.arg("--release".to_owned()).sandbox(|b| b.net(true));
+
+    let mut layered = Command::builder();
+    layered.merge_from(&defaults).merge_from(&file).merge_from(&cli);
+    let command = layered.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(
+        command.env,
+        vec![("RUST_LOG".to_owned(), "debug".to_owned())],
+    );
+    assert_eq!(command.current_dir.as_deref(), Some("/src"));
+    assert!(command.sandbox.net);
+    assert_eq!(command.sandbox.memory, 512);
+
+    let command = defaults.merge(cli).build().unwrap();
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, None);
+
+    let mut lower = Layers::builder();
+    lower.layer(1);
+    let mut upper = Layers::builder();
+    upper.layer(2).layer(3);
+    let layers = lower
+        .merge(upper)
+        .merge(Layers::builder())
+        .build()
+        .unwrap();
+    assert_eq!(layers.layers, vec![2, 3]);
+
+    let job = Job::builder()
+        .retries(1)
+        .merge(Job::builder().retries(5))
+        .name("nightly".to_owned())
+        .build();
+    assert_eq!(job.retries, Some(5));
+}
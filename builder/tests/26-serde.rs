@@ -0,0 +1,66 @@
+// With the `serde` feature of this crate enabled, #[builder(serde)] makes the
+// builder derive Serialize and Deserialize, so that part of a struct can come
+// from a config file and the rest from setters.
+//
+//     #[derive(Serialize, Deserialize)]
+//     pub struct CommandBuilder { ... }
+//
+// Every field may be missing from the input and then stays unset: `build()`
+// still reports required fields that were never set, and still applies
+// #[builder(default)]. Unset fields are left out when serializing. `each`
+// fields are read from arrays, and sub-builders from nested objects. A field
+// can be given a different key with #[builder(rename = "...")].
+//
+// The crate deriving Builder needs its own dependency on serde with the
+// `derive` feature.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(serde)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(rename = "cwd")]
+    current_dir: Option<String>,
+    #[builder(default = "3")]
+    retries: u32,
+    #[builder(sub_builder)]
+    sandbox: Sandbox,
+}
+
+#[derive(Builder, Debug)]
+#[builder(serde)]
+pub struct Sandbox {
+    #[builder(default)]
+    net: bool,
+}
+
+fn main() {
+    let json = r#"{
+        "args": ["build", "--release"],
+        "cwd": "/src",
+        "sandbox": { "net": true }
+    }"#;
+    let mut builder: CommandBuilder = serde_json::from_str(json).unwrap();
+    let err = builder.build().unwrap_err();
+    assert_eq!(err.to_string(), "missing field `executable`");
+
+    let command = builder.executable("cargo".to_owned()).build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir.as_deref(), Some("/src"));
+    assert_eq!(command.retries, 3);
+    assert!(command.sandbox.net);
+
+    let builder: CommandBuilder = serde_json::from_str("{}").unwrap();
+    assert!(builder.args.is_empty());
+
+    let mut builder = Command::builder();
+    builder.executable("ls".to_owned()).arg("-l".to_owned());
+    assert_eq!(
+        serde_json::to_string(&builder).unwrap(),
+        r#"{"executable":"ls","args":["-l"],"sandbox":{}}"#,
+    );
+}
@@ -0,0 +1,86 @@
+// Fields can be read from environment variables with #[builder(env = "...")].
+//
+//     impl CommandBuilder {
+//         pub fn from_env() -> Result<CommandBuilder, CommandBuilderError>;
+//         pub fn with_env(&mut self) -> Result<&mut CommandBuilder, CommandBuilderError>;
+//     }
+//
+// with_env() parses every variable that is set through `FromStr` and stores it
+// as if its setter had been called. Variables that are not set leave their
+// field untouched. An `each` field pushes the value as one element, or splits
+// it first when given `env_sep = "..."`. Sub-builders marked
+// #[builder(sub_builder(env))] read their own variables, by calling their own
+// with_env(). Builders reading no variables have neither method.
+//
+// A value that fails to parse is reported as
+//
+//     CommandBuilderError::EnvError { variable, field, message }
+//
+// naming both the variable and the field.
+
+use derive_builder::Builder;
+use std::env;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    #[builder(env = "TEST_ENV_EXECUTABLE")]
+    executable: String,
+    #[builder(each = "path", env = "TEST_ENV_PATH", env_sep = ":")]
+    paths: Vec<String>,
+    #[builder(env = "TEST_ENV_JOBS")]
+    jobs: Option<u32>,
+    #[builder(sub_builder(env))]
+    sandbox: Sandbox,
+}
+
+#[derive(Builder, Debug)]
+pub struct Sandbox {
+    #[builder(env = "TEST_ENV_NET", default)]
+    net: bool,
+}
+
+fn main() {
+    env::set_var("TEST_ENV_EXECUTABLE", "cargo");
+    env::set_var("TEST_ENV_PATH", "/bin:/usr/bin");
+    env::set_var("TEST_ENV_NET", "true");
+
+    let command = CommandBuilder::from_env().unwrap().build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.paths, vec!["/bin", "/usr/bin"]);
+    assert_eq!(command.jobs, None);
+    assert!(command.sandbox.net);
+
+    // Setters called afterwards take precedence.
+    let command = Command::builder()
+        .path("/opt/bin".to_owned())
+        .with_env()
+        .unwrap()
+        .executable("rustc".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "rustc");
+    assert_eq!(command.paths, vec!["/opt/bin", "/bin", "/usr/bin"]);
+
+    env::set_var("TEST_ENV_JOBS", "many");
+    let err = CommandBuilder::from_env().err().unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::EnvError {
+            variable: "TEST_ENV_JOBS",
+            field: "jobs",
+            message: "invalid digit found in string".to_owned(),
+        },
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid value in environment variable `TEST_ENV_JOBS` for field `jobs`: invalid digit found in string",
+    );
+
+    env::remove_var("TEST_ENV_JOBS");
+    env::set_var("TEST_ENV_NET", "maybe");
+    let err = CommandBuilder::from_env().err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "invalid value in environment variable `TEST_ENV_NET` for field `sandbox.net`: provided string was not `true` or `false`",
+    );
+}
@@ -0,0 +1,75 @@
+// Builder can also be derived on an enum whose variants have named fields.
+// Every variant gets a builder named after it, and an associated function on
+// the enum named after the variant in snake case:
+//
+//     impl Shape {
+//         pub fn circle() -> CircleBuilder { ... }
+//         pub fn rounded_rect() -> RoundedRectBuilder { ... }
+//     }
+//
+//     impl CircleBuilder {
+//         pub fn radius(&mut self, radius: f64) -> &mut Self { ... }
+//         pub fn build(&self) -> Result<Shape, CircleBuilderError> { ... }
+//     }
+//
+// Fields of the variants accept the same attributes as fields of a struct.
+// Attributes on the enum apply to the builders of all its variants.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub enum Shape {
+    Circle {
+        radius: f64,
+        label: Option<String>,
+    },
+    RoundedRect {
+        width: f64,
+        height: f64,
+        #[builder(default = "4.0")]
+        corner: f64,
+        #[builder(each = "tag")]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(typestate)]
+pub enum Event {
+    Click { x: i32, y: i32 },
+    Key { code: u32 },
+}
+
+fn main() {
+    let circle = Shape::circle().radius(1.5).build().unwrap();
+    assert_eq!(
+        circle,
+        Shape::Circle {
+            radius: 1.5,
+            label: None,
+        },
+    );
+
+    let rect = Shape::rounded_rect()
+        .width(2.0)
+        .height(3.0)
+        .tag("window".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(
+        rect,
+        Shape::RoundedRect {
+            width: 2.0,
+            height: 3.0,
+            corner: 4.0,
+            tags: vec!["window".to_owned()],
+        },
+    );
+
+    let err = Shape::circle().label("dot".to_owned()).build().unwrap_err();
+    assert_eq!(err, CircleBuilderError::UninitializedFields(vec!["radius"]));
+
+    let click = Event::click().x(1).y(2).build();
+    assert_eq!(click, Event::Click { x: 1, y: 2 });
+    assert_eq!(Event::key().code(13).build(), Event::Key { code: 13 });
+}
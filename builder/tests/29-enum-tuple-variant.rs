@@ -0,0 +1,12 @@
+// Variants without named fields have nothing for a builder to set, and are
+// rejected with an error pointing at the variant.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub enum Shape {
+    Circle { radius: f64 },
+    Point(f64, f64),
+}
+
+fn main() {}
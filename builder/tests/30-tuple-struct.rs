@@ -0,0 +1,60 @@
+// Tuple structs get builders too. Their fields have no names, so the setters
+// are named after their position, `_0`, `_1`, and so on, unless a field is
+// given a name with #[builder(name = "...")]. Fields are required or optional
+// just as in a struct with named fields.
+//
+//     impl Point3Builder {
+//         pub fn x(&mut self, x: f32) -> &mut Self { ... }
+//         pub fn _1(&mut self, _1: f32) -> &mut Self { ... }
+//         ...
+//     }
+//
+// A sub-builder in a tuple struct wraps its errors in a variant named after the
+// position, `Field0` for `_0`.
+//
+// Unit structs get a builder without setters, whose build() always succeeds.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Point3(#[builder(name = "x")] f32, f32, Option<f32>);
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Meters(#[builder(default)] f64);
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(typestate)]
+pub struct Pair(u8, #[builder(name = "second")] u8);
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Inner {
+    depth: u8,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Wrap(#[builder(sub_builder)] Inner);
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Marker;
+
+fn main() {
+    let point = Point3::builder().x(1.0)._1(2.0).build().unwrap();
+    assert_eq!(point, Point3(1.0, 2.0, None));
+
+    let err = Point3::builder()._2(3.0).build().unwrap_err();
+    assert_eq!(err.to_string(), "missing fields `x`, `_1`");
+
+    assert_eq!(Meters::builder().build().unwrap(), Meters(0.0));
+    assert_eq!(Meters::builder()._0(2.5).build().unwrap(), Meters(2.5));
+
+    assert_eq!(Pair::builder()._0(1).second(2).build(), Pair(1, 2));
+
+    let wrap = Wrap::builder()._0(|inner| inner.depth(3)).build().unwrap();
+    assert_eq!(wrap, Wrap(Inner { depth: 3 }));
+
+    let err = Wrap::builder().build().unwrap_err();
+    assert!(matches!(err, WrapBuilderError::Field0(_)));
+    assert_eq!(err.to_string(), "missing field `_0.depth`");
+
+    assert_eq!(Marker::builder().build().unwrap(), Marker);
+}
@@ -0,0 +1,77 @@
+// #[builder(each = "...")] is not limited to Vec. It works for the other std
+// collections as well:
+//
+//   - VecDeque, LinkedList, BinaryHeap, HashSet and BTreeSet, whose setter
+//     takes one element like for a Vec;
+//   - HashMap and BTreeMap, whose setter takes a key and a value.
+//
+// Any other collection implementing Extend and Default can be used by naming
+// its element type with #[builder(each(name = "...", item = "..."))].
+//
+// Every `each` field also gets a method adding all the elements of an
+// iterator at once:
+//
+//     impl CommandBuilder {
+//         pub fn extend_args(&mut self, items: impl IntoIterator<Item = String>) -> &mut Self;
+//     }
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: HashMap<String, String>,
+    #[builder(each = "label")]
+    labels: BTreeMap<&'static str, u32>,
+    #[builder(each = "feature")]
+    features: BTreeSet<String>,
+    #[builder(each = "stage")]
+    pipeline: VecDeque<u8>,
+    #[builder(each(name = "line", item = "char"))]
+    text: String,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned", setter(into))]
+pub struct Request {
+    #[builder(each = "header")]
+    headers: std::collections::HashMap<String, String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .extend_args(vec!["--release".to_owned(), "--locked".to_owned()])
+        .env("RUST_LOG".to_owned(), "debug".to_owned())
+        .extend_env([("A".to_owned(), "1".to_owned())])
+        .label("priority", 2)
+        .feature("b".to_owned())
+        .feature("a".to_owned())
+        .feature("b".to_owned())
+        .stage(1)
+        .stage(2)
+        .line('o')
+        .extend_text("k!".chars())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build", "--release", "--locked"]);
+    assert_eq!(command.env["RUST_LOG"], "debug");
+    assert_eq!(command.env["A"], "1");
+    assert_eq!(command.labels["priority"], 2);
+    assert_eq!(
+        command.features.into_iter().collect::<Vec<_>>(),
+        vec!["a", "b"],
+    );
+    assert_eq!(command.pipeline, VecDeque::from(vec![1, 2]));
+    assert_eq!(command.text, "ok!");
+
+    let request = Request::builder()
+        .header("Accept", "*/*")
+        .build()
+        .unwrap();
+    assert_eq!(request.headers["Accept"], "*/*");
+}
@@ -0,0 +1,63 @@
+// The names of the generated items can be changed, for example to avoid a
+// collision with an existing type called `CommandBuilder`:
+//
+//   - #[builder(name = "...")] on the struct names the builder type, and with
+//     it the error type, `<Name>Error`;
+//   - #[builder(build_fn(name = "...", vis = "..."))] renames build() and
+//     changes its visibility;
+//   - #[builder(setter(prefix = "..."))] on the struct or a field prefixes the
+//     setters named after their field;
+//   - #[builder(setter(name = "..."))] on a field names its setter outright.
+//
+// Element setters of `each` fields have the name given to `each`, which is
+// never prefixed. Methods that are only generated for some builders, like
+// `with_env()`, leave their names free for setters when they are not.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(
+    name = "CmdSpec",
+    build_fn(name = "finish", vis = "pub(crate)"),
+    setter(prefix = "with_")
+)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(setter(name = "cwd"))]
+    current_dir: Option<String>,
+    #[builder(setter(prefix = "set_"))]
+    timeout: Option<u32>,
+    #[builder(each = "var")]
+    env: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate, build_fn(name = "done"), setter(prefix = "with_"))]
+pub struct Job {
+    name: String,
+}
+
+fn main() {
+    let command = Command::builder()
+        .with_executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .with_args(vec!["test".to_owned()])
+        .cwd("..".to_owned())
+        .set_timeout(10)
+        .with_env(vec!["CI=1".to_owned()])
+        .finish()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["test"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.timeout, Some(10));
+    assert_eq!(command.env, vec!["CI=1"]);
+
+    let err: CmdSpecError = CmdSpec::default().finish().unwrap_err();
+    assert_eq!(err.to_string(), "missing field `executable`");
+
+    let job = Job::builder().with_name("nightly".to_owned()).done();
+    assert_eq!(job.name, "nightly");
+}
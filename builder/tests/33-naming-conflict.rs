@@ -0,0 +1,26 @@
+// Renamed and prefixed setters can end up with the same name as another
+// generated method, and sub-builder fields with the same error variant as a
+// built-in one. Each conflict is reported at the name that causes it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(setter(prefix = "with_"))]
+pub struct Command {
+    #[builder(each = "env")]
+    env: Vec<String>,
+    #[builder(env = "HOME")]
+    home: Option<String>,
+    #[builder(setter(name = "with_executable"))]
+    program: String,
+    executable: String,
+    #[builder(sub_builder)]
+    validation_error: Inner,
+}
+
+#[derive(Builder)]
+pub struct Inner {
+    depth: u8,
+}
+
+fn main() {}
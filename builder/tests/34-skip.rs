@@ -0,0 +1,91 @@
+// Fields that are computed rather than configured can be left out of the
+// builder with #[builder(skip)]. The builder has no storage or setter for
+// them, and build() fills them in with `Default::default()`, or with the
+// expression given as #[builder(skip = "...")]. Like a `default`, the
+// expression can refer to the fields declared before it. Type parameters and
+// lifetimes used only by skipped fields are still parameters of the builder.
+//
+// #[builder(setter(vis = "..."))] changes the visibility of the setters of a
+// field, for fields that may only be set from within the crate or module. On
+// the struct, it applies to all fields.
+
+mod command {
+    use derive_builder::Builder;
+
+    #[derive(Builder, Debug)]
+    pub struct Command {
+        pub executable: String,
+        #[builder(each = "arg")]
+        pub args: Vec<String>,
+        #[builder(skip = "args.len()")]
+        pub argc: usize,
+        #[builder(skip)]
+        pub cache: Vec<u8>,
+        #[builder(setter(vis = "pub(crate)"))]
+        pub id: Option<u32>,
+    }
+
+    pub fn with_id(builder: &mut CommandBuilder) {
+        builder.id(7);
+    }
+}
+
+use command::{Command, CommandBuilder};
+
+#[derive(derive_builder::Builder)]
+#[builder(typestate)]
+pub struct Job {
+    name: String,
+    #[builder(skip = "name.len()")]
+    len: usize,
+}
+
+#[derive(derive_builder::Builder)]
+pub struct Memo<T: Default> {
+    a: u32,
+    #[builder(skip)]
+    cache: Vec<T>,
+}
+
+#[derive(derive_builder::Builder)]
+#[builder(typestate)]
+pub struct Borrowed<'a, T: Default> {
+    a: u32,
+    #[builder(skip)]
+    cache: Option<&'a T>,
+}
+
+#[derive(derive_builder::Builder)]
+#[builder(const)]
+pub struct Label<'a> {
+    a: u32,
+    #[builder(skip = "None")]
+    text: Option<&'a str>,
+}
+
+fn main() {
+    let mut builder: CommandBuilder = Command::builder();
+    builder
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .arg("--release".to_owned());
+    command::with_id(&mut builder);
+    let command = builder.build().unwrap();
+    assert_eq!(command.argc, 2);
+    assert!(command.cache.is_empty());
+    assert_eq!(command.id, Some(7));
+
+    let job = Job::builder().name("nightly".to_owned()).build();
+    assert_eq!(job.len, 7);
+
+    let memo: Memo<String> = Memo::builder().a(1).build().unwrap();
+    assert!(memo.cache.is_empty());
+    let memo = memo.to_builder().build().unwrap();
+    assert_eq!(memo.a, 1);
+
+    let borrowed: Borrowed<String> = Borrowed::builder().a(2).build();
+    assert!(borrowed.cache.is_none());
+
+    const LABEL: Label = Label::builder().a(3).build();
+    assert!(LABEL.text.is_none());
+}
@@ -0,0 +1,74 @@
+// Fields can be gated on #[cfg(...)], and the builder follows along: the
+// storage, setters and build() initializer of such a field exist exactly when
+// the field does. In a typestate builder, a required field that is cfg'd away
+// does not have to be set before build().
+//
+// The #[doc] and #[deprecated] attributes of a field carry over to its
+// setters, so that using a deprecated field through the builder warns as
+// well. Further attributes can be put on the setters with
+// #[builder(setter(attrs(...)))], on a field or on the whole struct, and on
+// the builder struct itself with #[builder(struct_attrs(...))].
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(struct_attrs(derive(Clone, Debug)), setter(attrs(inline)))]
+pub struct Command {
+    /// Program to run.
+    executable: String,
+    #[builder(each = "arg", setter(attrs(must_use = "setters return the builder")))]
+    args: Vec<String>,
+    #[cfg(unix)]
+    uid: Option<u32>,
+    #[cfg(windows)]
+    creation_flags: u32,
+    #[deprecated(note = "use `args` instead")]
+    #[builder(default)]
+    arg_line: String,
+}
+
+#[derive(Builder, Debug)]
+#[builder(typestate)]
+pub struct Spawn {
+    executable: String,
+    #[cfg(unix)]
+    uid: u32,
+    #[cfg(not(unix))]
+    creation_flags: u32,
+}
+
+#[allow(deprecated)]
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    let _ = builder.arg("build".to_owned());
+    builder.arg_line("--release".to_owned());
+
+    #[cfg(unix)]
+    builder.uid(1000);
+    #[cfg(windows)]
+    builder.creation_flags(0x08000000);
+
+    // The builder derives Clone and Debug through struct_attrs.
+    let copy = builder.clone();
+    assert!(format!("{:?}", copy).contains("cargo"));
+
+    let command = copy.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.arg_line, "--release");
+    #[cfg(unix)]
+    assert_eq!(command.uid, Some(1000));
+    #[cfg(windows)]
+    assert_eq!(command.creation_flags, 0x08000000);
+
+    let spawn = Spawn::builder().executable("cargo".to_owned());
+    #[cfg(unix)]
+    let spawn = spawn.uid(1000);
+    #[cfg(not(unix))]
+    let spawn = spawn.creation_flags(0x08000000);
+    let spawn = spawn.build();
+    assert_eq!(spawn.executable, "cargo");
+    #[cfg(unix)]
+    assert_eq!(spawn.uid, 1000);
+}
@@ -0,0 +1,67 @@
+// Besides `Option<T>`, the fully qualified `std::option::Option<T>` and
+// `core::option::Option<T>`, with or without a leading `::`, are recognized
+// as optional fields.
+//
+// Any other way of naming Option, like an alias, cannot be recognized from
+// the tokens alone, so #[builder(optional)] marks such a field as optional
+// explicitly. Conversely #[builder(required)] makes an Option field one that
+// must be set, its setter taking the whole Option.
+//
+// #[builder(setter(strip_option = false))] keeps an optional field optional
+// but has its setter take the `Option<T>` instead of a `T`, which is handy
+// when forwarding a value that may itself be missing.
+
+use derive_builder::Builder;
+
+type Maybe<T> = Option<T>;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    current_dir: std::option::Option<String>,
+    env_clear: ::std::option::Option<bool>,
+    uid: core::option::Option<u32>,
+    #[builder(optional)]
+    gid: Maybe<u32>,
+    #[builder(required)]
+    stdin: Option<String>,
+    #[builder(setter(strip_option = false))]
+    stdout: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .uid(1000)
+        .gid(100)
+        .stdin(None)
+        .stdout(Some("out.log".to_owned()))
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.env_clear, None);
+    assert_eq!(command.uid, Some(1000));
+    assert_eq!(command.gid, Some(100));
+    assert_eq!(command.stdin, None);
+    assert_eq!(command.stdout, Some("out.log".to_owned()));
+
+    // `stdin` must be set even though it holds an Option.
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "missing field `stdin`");
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir("/".to_owned())
+        .stdin(Some("in.txt".to_owned()))
+        .stdout(None)
+        .build()
+        .unwrap();
+    assert_eq!(command.current_dir.as_deref(), Some("/"));
+    assert_eq!(command.stdin.as_deref(), Some("in.txt"));
+    assert_eq!(command.stdout, None);
+}
@@ -0,0 +1,70 @@
+// Some fields only make sense in certain combinations. Fields sharing a
+// #[builder(group = "...")] are mutually exclusive: at most one of them may
+// be set, or exactly one if any of them is marked `exactly_one`. A field with
+// #[builder(requires = "...")] can only be set along with the named field, or
+// with some field of the named group, and #[builder(conflicts_with = "...")]
+// is the opposite.
+//
+// build() checks all of these after the required fields, and reports every
+// broken rule at once through the `ConstraintViolations` variant of the
+// error.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    #[builder(group = "stdin", exactly_one)]
+    stdin_file: Option<String>,
+    #[builder(group = "stdin")]
+    stdin_bytes: Option<Vec<u8>>,
+    #[builder(requires = "group")]
+    user: Option<String>,
+    group: Option<String>,
+    #[builder(conflicts_with = "stdin")]
+    interactive: Option<bool>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cat".to_owned())
+        .stdin_file("input.txt".to_owned())
+        .user("nobody".to_owned())
+        .group("nogroup".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.stdin_file.as_deref(), Some("input.txt"));
+    assert_eq!(command.user.as_deref(), Some("nobody"));
+
+    let err = Command::builder()
+        .executable("cat".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::ConstraintViolations(vec![
+            "exactly one of `stdin_file`, `stdin_bytes` must be set",
+        ]),
+    );
+
+    let err = Command::builder()
+        .executable("cat".to_owned())
+        .stdin_file("input.txt".to_owned())
+        .stdin_bytes(b"hello".to_vec())
+        .user("nobody".to_owned())
+        .interactive(true)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "exactly one of `stdin_file`, `stdin_bytes` must be set; \
+         `user` requires `group`; \
+         `interactive` conflicts with `stdin`",
+    );
+
+    // Missing required fields are reported first.
+    let err = Command::builder().build().err().unwrap();
+    assert_eq!(err.to_string(), "missing field `executable`");
+}
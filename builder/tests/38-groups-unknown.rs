@@ -0,0 +1,24 @@
+// References in `requires` and `conflicts_with` are checked when the builder
+// is generated, so a misspelled field or group is a compile error pointing
+// at the name. So is a reference to an `each`, `sub_builder` or `skip` field,
+// which has no notion of being set.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(group = "stdin")]
+    stdin_file: Option<String>,
+    #[builder(requires = "groop")]
+    user: Option<String>,
+    group: Option<String>,
+    #[builder(conflicts_with = "stdn")]
+    interactive: Option<bool>,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(requires = "args")]
+    shell: Option<String>,
+}
+
+fn main() {}
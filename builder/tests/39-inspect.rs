@@ -0,0 +1,51 @@
+// A builder filled in step by step, say by an interactive prompt, needs to
+// look at what it holds so far. For each field the builder generates
+// `get_<field>`, returning the value if it was set, `is_<field>_set` and
+// `clear_<field>`, which unsets it again. An `each` field counts as set once
+// it has an element, and clearing it empties it.
+//
+// `missing_fields` lists the required fields that are not set yet: the ones
+// build() would report.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    nice: i32,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    assert_eq!(builder.missing_fields(), vec!["executable"]);
+    assert!(!builder.is_executable_set());
+    assert_eq!(builder.get_executable(), None);
+    assert!(!builder.is_args_set());
+    assert_eq!(builder.get_args(), None);
+
+    builder.executable("cargo".to_owned());
+    builder.arg("build".to_owned());
+    builder.current_dir("/".to_owned());
+    assert!(builder.missing_fields().is_empty());
+    assert_eq!(builder.get_executable().map(String::as_str), Some("cargo"));
+    assert_eq!(builder.get_args(), Some(&vec!["build".to_owned()]));
+    assert_eq!(builder.get_current_dir().map(String::as_str), Some("/"));
+    assert!(!builder.is_nice_set());
+
+    builder.clear_args();
+    builder.clear_current_dir();
+    assert!(!builder.is_args_set());
+    assert!(!builder.is_current_dir_set());
+
+    let command = builder.build().unwrap();
+    assert!(command.args.is_empty());
+    assert_eq!(command.current_dir, None);
+
+    builder.clear_executable();
+    assert_eq!(builder.missing_fields(), vec!["executable"]);
+    assert!(builder.build().is_err());
+}
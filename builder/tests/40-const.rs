@@ -0,0 +1,55 @@
+// With #[builder(const)] the builder can be used to define constants and
+// statics. The constructor, setters and build() are all `const fn`, with
+// setters taking and returning the builder by value. Since there is no good
+// way to handle a Result in a const context, build() returns the struct
+// itself and panics if a required field is missing.
+//
+// Only setters that can run in a const fn are allowed: `each`, `into`,
+// `try_setter` and `sub_builder` are rejected, as is a `default` that would
+// call `Default::default()`. A `default = "..."` expression must be const as
+// well. Fields whose type has a destructor, such as `String`, are rejected by
+// the compiler, since a const fn cannot drop them.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(const)]
+pub struct Limits<'a> {
+    name: &'a str,
+    max_files: u32,
+    #[builder(default = "max_files * 2")]
+    max_handles: u32,
+    nice: Option<i8>,
+}
+
+const DEFAULT: Limits<'static> = Limits::builder()
+    .name("default")
+    .max_files(16)
+    .build();
+
+static TABLE: [Limits<'static>; 2] = [
+    Limits::builder().name("small").max_files(4).nice(10).build(),
+    Limits::builder()
+        .name("large")
+        .max_files(1024)
+        .max_handles(4096)
+        .build(),
+];
+
+fn main() {
+    assert_eq!(
+        DEFAULT,
+        Limits {
+            name: "default",
+            max_files: 16,
+            max_handles: 32,
+            nice: None,
+        },
+    );
+    assert_eq!(TABLE[0].nice, Some(10));
+    assert_eq!(TABLE[1].max_handles, 4096);
+
+    // The builder is just as usable at runtime.
+    let limits = Limits::builder().name("runtime").max_files(1).build();
+    assert_eq!(limits.max_handles, 2);
+}
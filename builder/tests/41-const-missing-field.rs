@@ -0,0 +1,17 @@
+// Evaluated in a const context, the panic of build() on a missing field
+// becomes a compile error.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Limits {
+    max_files: u32,
+    max_handles: u32,
+}
+
+const LIMITS: Limits = Limits::builder().max_files(16).build();
+
+fn main() {
+    let _ = LIMITS.max_handles;
+}
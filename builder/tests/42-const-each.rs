@@ -0,0 +1,14 @@
+// Setters that cannot be const are rejected, pointing at the attribute or
+// field responsible.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Limits {
+    max_files: u32,
+    #[builder(each = "path")]
+    paths: Vec<&'static str>,
+}
+
+fn main() {}
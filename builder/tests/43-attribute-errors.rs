@@ -0,0 +1,32 @@
+// Mistakes in #[builder(...)] attributes are all reported at once, across the
+// struct and every field, instead of one per compilation. Misspelled keys get
+// a suggestion of the key that was probably meant, other unknown keys get the
+// list of valid ones, keys given twice are flagged, also inside repeated
+// `setter(...)`, and `each` is rejected on types that are not collections.
+// A malformed key does not hide the keys after it in the same attribute.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(patern = "owned", validate = "check", validate = "check_again")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", each = "argument")]
+    args: Vec<String>,
+    #[builder(each = "var")]
+    env: String,
+    #[builder(defualt)]
+    current_dir: Option<String>,
+    #[builder(setter(int))]
+    uid: u32,
+    #[builder(setter(name = "group"), setter(name = "gid"))]
+    group_id: u32,
+    #[builder(frobnicate)]
+    umask: u32,
+    #[builder(default = 1, eac = "x")]
+    nice: i32,
+    #[builder(setter(intoo), try_setterr)]
+    priority: u8,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// A sub-builder has to use the default mutable pattern: the outer setter
+// hands it out as `&mut`, and the outer build() builds it through `&self`.
+// One using the owned pattern is reported at the field. The outer builder
+// cannot use the immutable pattern, which would need the sub-builder to be
+// Clone.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Sandbox {
+    net: bool,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(sub_builder)]
+    sandbox: Sandbox,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Job {
+    #[builder(sub_builder)]
+    sandbox: Sandbox,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// A const fn cannot run destructors, so a const builder cannot have a field
+// whose type has one. The setter's error points at the field; build(), which
+// drops what is left of the builder, is still reported at the derive.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Limits {
+    max_files: u32,
+    name: String,
+}
+
+fn main() {}
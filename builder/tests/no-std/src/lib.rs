@@ -0,0 +1,135 @@
+// Builders derived with #[builder(no_std)] only refer to `core` and `alloc`,
+// so they can be used in crates without std. This crate is itself no_std,
+// and building it is the test: any `std` path left in the generated code is a
+// compile error. The tests below then link std for the test harness only.
+//
+// Run with `cargo test -p derive_builder_no_std_tests`.
+
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(test)]
+extern crate std;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std, try_setter)]
+pub struct Packet {
+    pub id: u16,
+    #[builder(each = "byte")]
+    pub payload: Vec<u8>,
+    pub label: Option<String>,
+    #[builder(default = "64")]
+    pub ttl: u8,
+    #[builder(group = "route", exactly_one)]
+    pub unicast: Option<u32>,
+    #[builder(group = "route")]
+    pub broadcast: Option<bool>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std, pattern = "owned", validate = "Frame::check")]
+pub struct Frame {
+    #[builder(sub_builder)]
+    pub packet: Packet,
+    pub checksum: u32,
+}
+
+impl Frame {
+    fn check(frame: &Frame) -> Result<(), &'static str> {
+        if frame.checksum == 0 {
+            Err("checksum cannot be zero")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std, typestate)]
+pub struct Header {
+    pub version: u8,
+    pub flags: Option<u8>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std, const)]
+pub struct Limits {
+    pub max_packets: u32,
+}
+
+pub const LIMITS: Limits = Limits::builder().max_packets(8).build();
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std, serde)]
+pub struct Route {
+    pub name: String,
+    pub metric: Option<u8>,
+    #[builder(each = "hop")]
+    pub hops: Vec<u16>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn packet() {
+        let packet = Packet::builder()
+            .id(1)
+            .byte(0xAB)
+            .byte(0xCD)
+            .unicast(7)
+            .try_ttl(32u32)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(packet.payload, vec![0xAB, 0xCD]);
+        assert_eq!(packet.ttl, 32);
+        assert_eq!(packet.label, None);
+
+        let err = Packet::builder().build().unwrap_err();
+        assert_eq!(err.to_string(), "missing field `id`");
+    }
+
+    #[test]
+    fn frame() {
+        let err = Frame::builder()
+            .packet(|packet| packet.id(1).broadcast(true))
+            .checksum(0)
+            .build()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "checksum cannot be zero");
+
+        let err = Frame::builder().checksum(1).build().unwrap_err();
+        assert_eq!(err.to_string(), "missing field `packet.id`");
+        assert!(core::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn header_and_limits() {
+        let header = Header::builder().version(2).build();
+        assert_eq!(header.flags, None);
+        assert_eq!(LIMITS.max_packets, 8);
+    }
+
+    #[test]
+    fn route() {
+        let builder: RouteBuilder = serde_json::from_str(r#"{"name": "edge"}"#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&builder).unwrap(),
+            r#"{"name":"edge"}"#
+        );
+
+        let route = builder.build().unwrap();
+        assert_eq!(route.name, "edge");
+        assert_eq!(route.metric, None);
+        assert!(route.hops.is_empty());
+    }
+}
@@ -1,13 +1,56 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-create-builder.rs");
-    //t.pass("tests/03-call-setters.rs");
-    //t.pass("tests/04-call-build.rs");
-    //t.pass("tests/05-method-chaining.rs");
-    //t.pass("tests/06-optional-field.rs");
-    //t.pass("tests/07-repeated-field.rs");
-    //t.compile_fail("tests/08-unrecognized-attribute.rs");
-    //t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-create-builder.rs");
+    t.pass("tests/03-call-setters.rs");
+    t.pass("tests/04-call-build.rs");
+    t.pass("tests/05-method-chaining.rs");
+    t.pass("tests/06-optional-field.rs");
+    t.pass("tests/07-repeated-field.rs");
+    t.compile_fail("tests/08-unrecognized-attribute.rs");
+    t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-typestate.rs");
+    t.compile_fail("tests/11-typestate-missing-field.rs");
+    t.pass("tests/12-default.rs");
+    t.compile_fail("tests/13-default-malformed.rs");
+    t.pass("tests/14-error-type.rs");
+    t.pass("tests/15-generics.rs");
+    t.pass("tests/16-setter-into.rs");
+    t.pass("tests/17-try-setter.rs");
+    t.pass("tests/18-validate.rs");
+    t.pass("tests/19-pattern-owned.rs");
+    t.pass("tests/20-pattern-immutable.rs");
+    t.pass("tests/21-pattern-mutable.rs");
+    t.compile_fail("tests/22-pattern-invalid.rs");
+    t.pass("tests/23-sub-builder.rs");
+    t.pass("tests/24-to-builder.rs");
+    t.pass("tests/25-merge.rs");
+    t.pass("tests/27-env.rs");
+    t.pass("tests/28-enum.rs");
+    t.compile_fail("tests/29-enum-tuple-variant.rs");
+    t.pass("tests/30-tuple-struct.rs");
+    t.pass("tests/31-each-collections.rs");
+    t.pass("tests/32-naming.rs");
+    t.compile_fail("tests/33-naming-conflict.rs");
+    t.pass("tests/34-skip.rs");
+    t.pass("tests/35-attrs.rs");
+    t.pass("tests/36-optional.rs");
+    t.pass("tests/37-groups.rs");
+    t.compile_fail("tests/38-groups-unknown.rs");
+    t.pass("tests/39-inspect.rs");
+    t.pass("tests/40-const.rs");
+    t.compile_fail("tests/41-const-missing-field.rs");
+    t.compile_fail("tests/42-const-each.rs");
+    t.compile_fail("tests/43-attribute-errors.rs");
+    t.compile_fail("tests/44-sub-builder-pattern.rs");
+    t.compile_fail("tests/45-const-drop.rs");
+
+    // #[builder(no_std)] is tested by the no_std crate in tests/no-std, run
+    // with `cargo test -p derive_builder_no_std_tests`.
+
+    // Run with `cargo test --features serde`, as CI does.
+    if cfg!(feature = "serde") {
+        t.pass("tests/26-serde.rs");
+    }
 }